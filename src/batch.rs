@@ -0,0 +1,157 @@
+use crate::{history, journal::JournalEntry, log, type_in_game};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// ================= ОЧЕРЕДЬ НАКАЗАНИЙ =================
+// Одна и та же команда для списка ID ("12, 15, 20-25") отправляется по очереди
+// с паузой, чтобы чат успевал принять каждую. Очередь можно остановить.
+
+/// Больше ID за раз не берем: "1-9999" - скорее опечатка, чем рейд.
+pub const MAX_IDS: usize = 50;
+const PAUSE_MS: u64 = 1500;
+
+/// "12, 15 20-25; 30" → ["12", "15", "20", ..., "25", "30"]; повторы убираются.
+pub fn parse_id_list(input: &str) -> Result<Vec<String>, String> {
+    // "20 - 25" и "20–25" (тире из Word/Discord) - тоже диапазоны
+    let input = input.replace(['–', '—'], "-");
+    let input = input.split('-').map(str::trim).collect::<Vec<_>>().join("-");
+    let mut ids: Vec<String> = Vec::new();
+    for part in input.split(|c: char| c == ',' || c == ';' || c.is_whitespace()).filter(|p| !p.is_empty()) {
+        let range: Vec<u32> = match part.split_once('-') {
+            Some((a, b)) => {
+                let (a, b): (u32, u32) = match (a.trim().parse(), b.trim().parse()) {
+                    (Ok(a), Ok(b)) => (a, b),
+                    _ => return Err(format!("'{}': диапазон ID должен быть вида 20-25", part)),
+                };
+                if a > b { return Err(format!("'{}': начало диапазона больше конца", part)); }
+                if (b - a) as usize >= MAX_IDS { return Err(format!("'{}': больше {} ID за раз", part, MAX_IDS)); }
+                (a..=b).collect()
+            }
+            None => vec![part.parse().map_err(|_| format!("'{}': ID должен быть числом", part))?],
+        };
+        for id in range {
+            let id = id.to_string();
+            if !ids.contains(&id) { ids.push(id); }
+        }
+    }
+    if ids.len() > MAX_IDS { return Err(format!("{} ID - больше {} за раз", ids.len(), MAX_IDS)); }
+    Ok(ids)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BatchState { Pending, Sending, Sent, Cancelled }
+
+impl BatchState {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            BatchState::Pending => "⏳",
+            BatchState::Sending => "▶",
+            BatchState::Sent => "✅",
+            BatchState::Cancelled => "⛔",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BatchItem {
+    pub command: String,
+    pub state: BatchState,
+    /// Запись для журнала; забирается, когда команда отправлена
+    pub entry: Option<JournalEntry>,
+}
+
+#[derive(Default)]
+pub struct BatchQueue {
+    pub items: Arc<Mutex<Vec<BatchItem>>>,
+    running: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl BatchQueue {
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        if !self.is_running() {
+            if let Ok(mut items) = self.items.lock() { items.clear(); }
+        }
+    }
+
+    pub fn start(&self, commands: Vec<(String, JournalEntry)>) {
+        if self.is_running() { return; }
+        if let Ok(mut items) = self.items.lock() {
+            *items = commands.into_iter().map(|(command, entry)| BatchItem { command, state: BatchState::Pending, entry: Some(entry) }).collect();
+        }
+        self.cancel.store(false, Ordering::Relaxed);
+        self.running.store(true, Ordering::Relaxed);
+        let (items, running, cancel) = (self.items.clone(), self.running.clone(), self.cancel.clone());
+
+        thread::spawn(move || {
+            let total = items.lock().map(|i| i.len()).unwrap_or(0);
+            log(&format!("Batch: sending {} commands", total));
+            for i in 0..total {
+                if cancel.load(Ordering::Relaxed) {
+                    if let Ok(mut items) = items.lock() {
+                        for item in items.iter_mut().filter(|it| it.state == BatchState::Pending) { item.state = BatchState::Cancelled; }
+                    }
+                    log(&format!("Batch: cancelled after {} of {}", i, total));
+                    break;
+                }
+                let command = match items.lock() {
+                    Ok(mut items) => { items[i].state = BatchState::Sending; items[i].command.clone() }
+                    Err(_) => break,
+                };
+                // type_in_game печатает в своем потоке и сбрасывает флаг, когда закончит
+                history::record("F7 очередь", &command);
+                let typing = Arc::new(AtomicBool::new(true));
+                type_in_game(None, command, true, true, Some(typing.clone()));
+                while typing.load(Ordering::Relaxed) { thread::sleep(Duration::from_millis(50)); }
+                if let Ok(mut items) = items.lock() { items[i].state = BatchState::Sent; }
+                if i + 1 < total { thread::sleep(Duration::from_millis(PAUSE_MS)); }
+            }
+            running.store(false, Ordering::Relaxed);
+        });
+    }
+
+    /// Записи журнала для уже отправленных команд (каждая выдается один раз).
+    pub fn take_sent_entries(&self) -> Vec<JournalEntry> {
+        let Ok(mut items) = self.items.lock() else { return Vec::new() };
+        items.iter_mut().filter(|it| it.state == BatchState::Sent).filter_map(|it| it.entry.take()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input_gives_no_ids() {
+        assert_eq!(parse_id_list(""), Ok(vec![]));
+        assert_eq!(parse_id_list(" ,; "), Ok(vec![]));
+    }
+
+    #[test]
+    fn lists_and_ranges() {
+        assert_eq!(parse_id_list("12, 15 20-22; 30").unwrap(), ["12", "15", "20", "21", "22", "30"]);
+        assert_eq!(parse_id_list("20 - 22").unwrap(), ["20", "21", "22"]);
+        assert_eq!(parse_id_list("20–22, 21").unwrap(), ["20", "21", "22"]);
+        assert_eq!(parse_id_list("7-7").unwrap(), ["7"]);
+    }
+
+    #[test]
+    fn bad_input_is_rejected() {
+        assert!(parse_id_list("25-20").is_err());
+        assert!(parse_id_list("12a").is_err());
+        assert!(parse_id_list("-5").is_err());
+        assert!(parse_id_list("1-2-3").is_err());
+        assert!(parse_id_list("1-50").is_ok());
+        assert!(parse_id_list("1-51").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::data::{AdminCommand, ArgKind};
+use crate::punish::PunishDuration;
+
+// ================= ФОРМА КОМАНДЫ F6 =================
+// Команда с аргументами открывается формой: каждое поле проверяется по типу,
+// а в игру уходит уже собранная строка, а не "/mutechat " с курсором.
+
+/// "/mutechat (/mc) [ID] [Срок] [Причина]"
+pub fn signature(command: &AdminCommand) -> String {
+    let mut text = command.name.to_string();
+    if !command.aliases.is_empty() { text.push_str(&format!(" ({})", command.aliases.join(", "))); }
+    for arg in command.args { text.push_str(&format!(" [{}]", arg.name)); }
+    text
+}
+
+pub fn arg_hint(kind: ArgKind) -> &'static str {
+    match kind {
+        ArgKind::PlayerId => "12156",
+        ArgKind::Vehicle => "12156:3",
+        ArgKind::Duration => "30mi / 3d",
+        ArgKind::Reason => "текст",
+        ArgKind::Number => "100",
+        ArgKind::Text => "одно слово",
+    }
+}
+
+/// Проверенное значение в том виде, в котором его ждет сервер.
+fn check_arg(kind: ArgKind, value: &str, command: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() { return Err("не заполнено".to_string()); }
+    let is_number = |v: &str| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit());
+    match kind {
+        ArgKind::PlayerId | ArgKind::Number if !is_number(value) => Err("нужно число".to_string()),
+        ArgKind::Vehicle => match value.split_once(':') {
+            Some((id, car)) if is_number(id) && is_number(car) => Ok(value.to_string()),
+            _ => Err("нужно ID:IDauto, например 12156:3".to_string()),
+        },
+        // Сроки пишутся как в F7, а команде отдаются в ее единицах (мьюты - только минуты)
+        ArgKind::Duration => PunishDuration::parse(value)?.command_arg(command),
+        ArgKind::Text if value.contains(char::is_whitespace) => Err("без пробелов".to_string()),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Ошибки по полям формы (индекс аргумента, текст); пусто - можно отправлять.
+pub fn check_form(command: &AdminCommand, values: &[String]) -> Vec<(usize, String)> {
+    command.args.iter().enumerate().filter_map(|(i, arg)| check_arg(arg.kind, values.get(i).map(|v| v.as_str()).unwrap_or(""), command.name).err().map(|e| (i, e))).collect()
+}
+
+/// Готовая строка команды или None, если форма заполнена с ошибками.
+/// `short` - отправить короткую форму (/mc вместо /mutechat).
+pub fn build_command(command: &AdminCommand, values: &[String], short: bool) -> Option<String> {
+    let mut line = command.send_name(short).to_string();
+    for (i, arg) in command.args.iter().enumerate() {
+        let value = check_arg(arg.kind, values.get(i).map(|v| v.as_str()).unwrap_or(""), command.name).ok()?;
+        if !value.is_empty() { line.push(' '); line.push_str(&value); }
+    }
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::find_command;
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn errors(command: &str, input: &[&str]) -> Vec<(usize, String)> {
+        check_form(find_command(command).unwrap(), &values(input))
+    }
+
+    #[test]
+    fn required_args_must_be_filled() {
+        assert_eq!(errors("/ban", &[]).iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(errors("/ban", &["12", "3d", "  "]), [(2, "не заполнено".to_string())]);
+        assert!(errors("/ban", &["12", "3d", "3.2 ПП"]).is_empty());
+        assert!(errors("/adminmode", &[]).is_empty());
+    }
+
+    #[test]
+    fn numbers_vehicles_and_durations_are_checked() {
+        assert_eq!(errors("/spectate", &["12a"]), [(0, "нужно число".to_string())]);
+        assert!(errors("/teleportcar", &["12156:3"]).is_empty());
+        assert_eq!(errors("/teleportcar", &["12156"]).len(), 1);
+        assert_eq!(errors("/teleportcar", &["12156:x"]).len(), 1);
+        assert_eq!(errors("/ban", &["12", "3D", "мат"]).iter().map(|(i, _)| *i).collect::<Vec<_>>(), [1]);
+        assert_eq!(errors("/setfractionrank", &["12", "LSPD", "x"]).iter().map(|(i, _)| *i).collect::<Vec<_>>(), [2]);
+        assert_eq!(errors("/setfractionrank", &["12", "LS PD", "3"]), [(1, "без пробелов".to_string())]);
+    }
+
+    #[test]
+    fn built_command_text() {
+        let build = |command: &str, input: &[&str], short: bool| build_command(find_command(command).unwrap(), &values(input), short);
+        assert_eq!(build("/ban", &[" 12 ", "3d", " 3.2 ПП | №1234 "], false).as_deref(), Some("/ban 12 3d 3.2 ПП | №1234"));
+        // Муты принимают только минуты
+        assert_eq!(build("/mutechat", &["12", "1d", "мат"], false).as_deref(), Some("/mutechat 12 1440mi мат"));
+        assert_eq!(build("/mutechat", &["12", "30mi", "мат"], true).as_deref(), Some("/mc 12 30mi мат"));
+        assert_eq!(build("/teleportcar", &["12156:3"], false).as_deref(), Some("/teleportcar 12156:3"));
+        assert_eq!(build("/adminmode", &[], false).as_deref(), Some("/adminmode"));
+        assert_eq!(build("/ban", &["12", "", "мат"], false), None);
+        assert_eq!(signature(find_command("/mutechat").unwrap()), "/mutechat (/mc) [ID] [Срок] [Причина]");
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::OnceLock;
 
 pub struct Rank {
     pub name: String,
@@ -440,7 +441,7 @@ pub fn get_auto_replacements() -> Vec<(&'static str, &'static str)> {
         ("Хостинги видео", "Загрузите запись на Twitch, YouTube, RuTube, Trovo, Imgur, yapx. ru, Google Диск, Яндекс Диск и прикрепите ссылку в репорт."),
         ("Взаимодействие с игроками", "Уважаемый игрок, к сожалению администрация не имеет право вмешиваться в RP-Процесс, это нарушает наш регламент работы."),
         ("Просмотр видео", "Начал просмотр вашего доказательство, это может занять некотрое время."),
-        ("Наказан", "Игрок со статическим ID -  | Будет наказан за  | Длительность наказание"),
+        ("Наказан", "Игрок со статическим ID - {last_id} | Будет наказан за {last_article} | Длительность наказание"),
         ("Без док-вв", "К сожалению без доказательств наказать мы не сможем, советуем вам включать откат с помощью вашего ПО или как-то записывать происходящее что-бы мы администрация смогла наказать за нарушения правил проекта."),
         
 
@@ -505,86 +506,181 @@ pub fn get_mp_commands(my_id: &str) -> Vec<MpPreset> {
     ]
 }
 
-pub fn get_admin_commands() -> Vec<(&'static str, &'static str)> {
+/// Уровень "Модер" - 0, дальше 1-7 lvl как на сервере.
+pub const MAX_ADMIN_LEVEL: u8 = 7;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ArgKind {
+    /// Статический ID игрока
+    PlayerId,
+    /// "ID:IDauto" - авто конкретного игрока
+    Vehicle,
+    /// Срок как в F7: 30mi, 3d
+    Duration,
+    /// Текст до конца строки; всегда последний аргумент
+    Reason,
+    Number,
+    /// Одно слово: модель, оружие, фракция
+    Text,
+}
+
+#[derive(Clone, Copy)]
+pub struct Arg {
+    pub name: &'static str,
+    pub kind: ArgKind,
+}
+
+const PLAYER: Arg = Arg { name: "ID", kind: ArgKind::PlayerId };
+const VEHICLE: Arg = Arg { name: "ID:IDauto", kind: ArgKind::Vehicle };
+const DURATION: Arg = Arg { name: "Срок", kind: ArgKind::Duration };
+const REASON: Arg = Arg { name: "Причина", kind: ArgKind::Reason };
+
+pub struct AdminCommand {
+    pub name: &'static str,
+    /// Короткие формы, которые сервер понимает так же (/mc для /mutechat)
+    pub aliases: &'static [&'static str],
+    pub args: &'static [Arg],
+    pub desc: &'static str,
+    /// Минимальный уровень админки, с которого команда доступна
+    pub level: u8,
+}
+
+impl AdminCommand {
+    /// Имя для отправки: самое короткое сокращение, если админ предпочитает короткие формы.
+    pub fn send_name(&self, short: bool) -> &'static str {
+        if !short { return self.name; }
+        self.aliases.iter().copied().min_by_key(|a| a.len()).filter(|a| a.len() < self.name.len()).unwrap_or(self.name)
+    }
+
+    /// Поиск F6: имя, сокращения и описание; "/" в запросе не обязателен.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().trim_start_matches('/').to_lowercase();
+        if query.is_empty() { return true; }
+        std::iter::once(self.name).chain(self.aliases.iter().copied()).any(|n| n.to_lowercase().contains(&query)) || self.desc.to_lowercase().contains(&query)
+    }
+
+    /// Сокращение, по которому нашлась команда (если не нашлась по полному имени).
+    pub fn matched_alias(&self, query: &str) -> Option<&'static str> {
+        let query = query.trim().trim_start_matches('/').to_lowercase();
+        if query.is_empty() || self.name.to_lowercase().contains(&query) { return None; }
+        self.aliases.iter().copied().find(|a| a.to_lowercase().contains(&query))
+    }
+}
+
+pub fn level_label(level: u8) -> String {
+    if level == 0 { "Модер".to_string() } else { format!("{} lvl", level) }
+}
+
+static ADMIN_COMMANDS: OnceLock<Vec<AdminCommand>> = OnceLock::new();
+
+/// Каталог команд. Собирается один раз: check_level вызывается на каждом кадре.
+pub fn admin_commands() -> &'static [AdminCommand] {
+    ADMIN_COMMANDS.get_or_init(get_admin_commands)
+}
+
+/// Команда каталога по имени или короткой форме из набранной строки.
+pub fn find_command(command: &str) -> Option<&'static AdminCommand> {
+    let name = command.split_whitespace().next().unwrap_or("");
+    admin_commands().iter().find(|c| c.name == name || c.aliases.contains(&name))
+}
+
+/// Уровень для набранной команды. Неизвестные команды считаются доступными всем.
+pub fn command_level(command: &str) -> u8 {
+    find_command(command).map(|c| c.level).unwrap_or(0)
+}
+
+/// Проверка всех строк команды против уровня админа.
+pub fn check_level(commands: &str, admin_level: u8) -> Result<(), String> {
+    for line in commands.lines().filter(|l| !l.trim().is_empty()) {
+        let level = command_level(line);
+        if level > admin_level {
+            let name = line.split_whitespace().next().unwrap_or("");
+            return Err(format!("{} доступна с уровня {}, у вас {}", name, level_label(level), level_label(admin_level)));
+        }
+    }
+    Ok(())
+}
+
+fn get_admin_commands() -> Vec<AdminCommand> {
     vec![
-        ("/adminbase", "(/ab) - ТП на админ-базу (Модер)"),
-        ("/godmode", "(/gm) - Бессмертие/режим бога (Модер)"),
-        ("/uncuff", "/uncuff [ID] - Снять наручники (Модер)"),
-        ("/dimensionstat", "Количество игроков в виртуальном мире (Модер)"),
-        ("/adminmode", "(/noclip, /nc) - Режим невидимости/прохождения сквозь стены (Модер)"),
-        ("/esp", "Информация об игроках/машинах (Модер)"),
-        ("/goto", "[ID] - ТП к игроку (Модер)"),
-        ("/tp", "[ID] - ТП игрока к себе (Модер)"),
-        ("/return", "[ID] (/ret) - Вернуть игрока на последнее место (Модер)"),
-        ("/spectate", "[ID] (/spec) - Следить за игроком (Модер)"),
-        ("/callcar", "[ID] (/ccar) - ТП авто игрока на ближайшую парковку (Модер)"),
-        ("/teleportcar", "[ID:IDauto] (/tpcar) - ТП авто к себе (Модер)"),
-        ("/flipcar", "[ID:IDauto] - Перевернуть авто (Модер)"),
-        ("/freeze", "[ID] - Заморозить игрока (Модер)"),
-        ("/unfreeze", "[ID] - Разморозить игрока (Модер)"),
-        ("/respawn", "[ID] - Воскресить игрока (Модер)"),
-        ("/revive", "[ID] - Поднять игрока с нока (Модер)"),
-        ("/unpacifist", "[ID] [Причина] - Снять режим пацифиста (Модер)"),
-        ("/setpos", "ТП по координатам (Модер)"),
-        ("/unrk", "[ID] - Удалтть РК зону игроку (Модер)"),
-        ("/kick", "[ID] [Причина] — Исключить игрока (Модер)"),
-        ("/ban", "[ID] [Срок] [Причина] — Отправить в деморган (до 1 дня) / заблокировать (более 1 дня) (Модер)"),
-        ("/mutechat", "[ID] [Срок] [Причина] (/mc) — Заблокировать чат игроку (Модер)"),
-        ("/mutevoice", "[ID] [Срок] [Причина] (/mv) — Заблокировать голосовой чат игроку (Модер)"),
-        ("/mutereport", "[ID] [Срок] [Причина] (/mr) — Заблокировать репорт игроку (Модер)"),
-        ("/getwarns", "[ID] (/warns) - Информация о варнах (Модер)"),
-        ("/infoban", "[ID] - Информация о банах (Модер)"),
-        ("/pacifist", "[ID] [Срок] [Причина] — Выдать редим пацифиста (Модер)"),
-        ("/unmute", "[ID] - Снять запрет на голос/чат/репорт (Модер)"),
-        ("/deafness", "Выдать иконку глухого (Модер)"),
-        ("/numbness", "Выдать иконку немого (Модер)"),
-        ("/unicon", "Снять все иконки (Модер)"),
-        ("/ban_market_content_creation", "[ID] [Срок] [Причина] - Выдать блокировку маркетплейса игроку (Модер)"),
-        ("/warn", "[ID] [Причина] — Выдать предупреждение (1 lvl)"),
-        ("/unarrest", "[ID] [Причина] — Выпустить из тюрьмы (1 lvl)"),
-        ("/unban", "[ID] [Причина] — Снять бан (2 lvl)"),
-        ("/spawnvehicle", "[ID auto] [ID color] (/veh) — Заспавнить авто (2 lvl)"),
-        ("/fixcar", "[ID:IDauto] - Отремонтировать авто (2 lvl)"),
-        ("/vehiclerows", "[ID auto] (/rveh) - Создать множество авто (2 lvl)"),
-        ("/clearvehicles", "(/clearveh) - Удалить все созданные авто (2 lvl)"),
-        ("/sethealth", "[ID] [HP] (/sethp) - Выдать ХП (2 lvl)"),
-        ("/sethealthall", "[HP] (/sethpall) - Выдать ХП всем рядом (2 lvl)"),
-        ("/setarmor", "[ID] [ARMOR] - Выдать броню (2 lvl)"),
-        ("/setarmorall", "[ARMOR] - Выдать броню всем рядом (2 lvl)"),
-        ("/freezeall", "Заморозить всех рядом (2 lvl)"),
-        ("/unfreezeall", "Разморозить всех рядом (2 lvl)"),
-        ("/givegun", "[ID] [ID gun] [Patron] (/gun) - Выдать оружие (2 lvl)"),
-        ("/givegunall", "[ID gun] [Patron] (/gunall) - Выдать оружие всем рядом (2 lvl)"),
-        ("/model", "[ID] [ID skin] - Выдать скин игроку (2 lvl)"),
-        ("/modelall", "[ID skin] - Выдать скин всем рядом"),
-        ("/muteall", "Выдать запрет на голос всем рядом (2 lvl)"),
-        ("/unmuteall", "Снять запрет на голос всем рядом (2 lvl)"),
-        ("/killall", "Убить всех рядом (2 lvl)"),
-        ("/dimension", "[ID] [ID dim] (/dim) - ТП в виртуальный мире (3 lvl)"),
-        ("/dimension_close", "Закрыть виртуальный мир (3 lvl)"),
-        ("/gomp", "[PLAYERS] [TIME] [NAME] - запустить МП (3 lvl)"),
-        ("/gomp_stop", "Закрыть МП (3 lvl)"),
-        ("/unwarn", "[ID] [Причина] - Снять последнее предупреждение (3 lvl)"),
-        ("/setleader", "[ID] [FRAC_NAME] - Выдать лидерку (5 lvl)"),
-        ("/unsetleader", "[ID] - Снять лидерку (5 lvl)"),
-        ("/setfractionrank", "[ID] [FRAC_NAME] [RANK] - Установить ранг во фракции (5 lvl)"),
-        ("/setfraction", "[ID] [FRAC_NAME] - Установить фракцию (5 lvl)"),
-        ("/getfractionname", "Узнать ID фракций (5 lvl)"),
-        ("/forbid_buy_chips", "Запрет на покупку фишек (5 lvl)"),
-        ("/unforbid_buy_chips", "Снять запрет на покупку фишек (5 lvl)"),
-        ("/greenzoneoff", "Выключить ЗЗ (5 lvl)"),
-        ("/greenzoneon", "Включить ЗЗ (5 lvl)"),
-        ("/setstaff", "[ID] [RANK] - Выдать админ ранг (5 lvl)"),
-        ("/prolongedban", "(/pban) Продлить бан более 30 дней (5 lvl)"),
-        ("/strictban", "(/sban) Выдать бан более 30 дней (5 lvl)"),
-        ("/giveitem", "Выдача предметов (7 lvl)"),
-        ("/changename", "Поменять никнейм (7 lvl)"),
-        ("/wipestorage", "Обнулить склад (7 lvl)"),
-        ("/addcredits", "Выдать донат (7 lvl)"),
-        ("/addmoney", "Выдать деньги (7 lvl)"),
-        ("/givecase", "Выдать кейс (7 lvl)"),
-        ("/perma", "Бесрочный бан на всех серверах (7 lvl)"),
-        ("/wizardreset", "[ID] - сменить пол (7 lvl)"),
+        AdminCommand { name: "/adminbase", aliases: &["/ab"], args: &[], desc: "ТП на админ-базу", level: 0 },
+        AdminCommand { name: "/godmode", aliases: &["/gm"], args: &[], desc: "Бессмертие/режим бога", level: 0 },
+        AdminCommand { name: "/uncuff", aliases: &[], args: &[PLAYER], desc: "Снять наручники", level: 0 },
+        AdminCommand { name: "/dimensionstat", aliases: &[], args: &[], desc: "Количество игроков в виртуальном мире", level: 0 },
+        AdminCommand { name: "/adminmode", aliases: &["/noclip", "/nc"], args: &[], desc: "Режим невидимости/прохождения сквозь стены", level: 0 },
+        AdminCommand { name: "/esp", aliases: &[], args: &[], desc: "Информация об игроках/машинах", level: 0 },
+        AdminCommand { name: "/goto", aliases: &[], args: &[PLAYER], desc: "ТП к игроку", level: 0 },
+        AdminCommand { name: "/tp", aliases: &[], args: &[PLAYER], desc: "ТП игрока к себе", level: 0 },
+        AdminCommand { name: "/return", aliases: &["/ret"], args: &[PLAYER], desc: "Вернуть игрока на последнее место", level: 0 },
+        AdminCommand { name: "/spectate", aliases: &["/spec"], args: &[PLAYER], desc: "Следить за игроком", level: 0 },
+        AdminCommand { name: "/callcar", aliases: &["/ccar"], args: &[PLAYER], desc: "ТП авто игрока на ближайшую парковку", level: 0 },
+        AdminCommand { name: "/teleportcar", aliases: &["/tpcar"], args: &[VEHICLE], desc: "ТП авто к себе", level: 0 },
+        AdminCommand { name: "/flipcar", aliases: &[], args: &[VEHICLE], desc: "Перевернуть авто", level: 0 },
+        AdminCommand { name: "/freeze", aliases: &[], args: &[PLAYER], desc: "Заморозить игрока", level: 0 },
+        AdminCommand { name: "/unfreeze", aliases: &[], args: &[PLAYER], desc: "Разморозить игрока", level: 0 },
+        AdminCommand { name: "/respawn", aliases: &[], args: &[PLAYER], desc: "Воскресить игрока", level: 0 },
+        AdminCommand { name: "/revive", aliases: &[], args: &[PLAYER], desc: "Поднять игрока с нока", level: 0 },
+        AdminCommand { name: "/unpacifist", aliases: &[], args: &[PLAYER, REASON], desc: "Снять режим пацифиста", level: 0 },
+        AdminCommand { name: "/setpos", aliases: &[], args: &[Arg { name: "Координаты", kind: ArgKind::Reason }], desc: "ТП по координатам", level: 0 },
+        AdminCommand { name: "/unrk", aliases: &[], args: &[PLAYER], desc: "Удалтть РК зону игроку", level: 0 },
+        AdminCommand { name: "/kick", aliases: &[], args: &[PLAYER, REASON], desc: "Исключить игрока", level: 0 },
+        AdminCommand { name: "/ban", aliases: &[], args: &[PLAYER, DURATION, REASON], desc: "Отправить в деморган (до 1 дня) / заблокировать (более 1 дня)", level: 0 },
+        AdminCommand { name: "/mutechat", aliases: &["/mc"], args: &[PLAYER, DURATION, REASON], desc: "Заблокировать чат игроку", level: 0 },
+        AdminCommand { name: "/mutevoice", aliases: &["/mv"], args: &[PLAYER, DURATION, REASON], desc: "Заблокировать голосовой чат игроку", level: 0 },
+        AdminCommand { name: "/mutereport", aliases: &["/mr"], args: &[PLAYER, DURATION, REASON], desc: "Заблокировать репорт игроку", level: 0 },
+        AdminCommand { name: "/getwarns", aliases: &["/warns"], args: &[PLAYER], desc: "Информация о варнах", level: 0 },
+        AdminCommand { name: "/infoban", aliases: &[], args: &[PLAYER], desc: "Информация о банах", level: 0 },
+        AdminCommand { name: "/pacifist", aliases: &[], args: &[PLAYER, DURATION, REASON], desc: "Выдать редим пацифиста", level: 0 },
+        AdminCommand { name: "/unmute", aliases: &[], args: &[PLAYER], desc: "Снять запрет на голос/чат/репорт", level: 0 },
+        AdminCommand { name: "/deafness", aliases: &[], args: &[], desc: "Выдать иконку глухого", level: 0 },
+        AdminCommand { name: "/numbness", aliases: &[], args: &[], desc: "Выдать иконку немого", level: 0 },
+        AdminCommand { name: "/unicon", aliases: &[], args: &[], desc: "Снять все иконки", level: 0 },
+        AdminCommand { name: "/ban_market_content_creation", aliases: &[], args: &[PLAYER, DURATION, REASON], desc: "Выдать блокировку маркетплейса игроку", level: 0 },
+        AdminCommand { name: "/warn", aliases: &[], args: &[PLAYER, REASON], desc: "Выдать предупреждение", level: 1 },
+        AdminCommand { name: "/unarrest", aliases: &[], args: &[PLAYER, REASON], desc: "Выпустить из тюрьмы", level: 1 },
+        AdminCommand { name: "/unban", aliases: &[], args: &[PLAYER, REASON], desc: "Снять бан", level: 2 },
+        AdminCommand { name: "/spawnvehicle", aliases: &["/veh"], args: &[Arg { name: "Модель авто", kind: ArgKind::Text }, Arg { name: "Цвет", kind: ArgKind::Number }], desc: "Заспавнить авто", level: 2 },
+        AdminCommand { name: "/fixcar", aliases: &[], args: &[VEHICLE], desc: "Отремонтировать авто", level: 2 },
+        AdminCommand { name: "/vehiclerows", aliases: &["/rveh"], args: &[Arg { name: "Модель авто", kind: ArgKind::Text }], desc: "Создать множество авто", level: 2 },
+        AdminCommand { name: "/clearvehicles", aliases: &["/clearveh"], args: &[], desc: "Удалить все созданные авто", level: 2 },
+        AdminCommand { name: "/sethealth", aliases: &["/sethp"], args: &[PLAYER, Arg { name: "HP", kind: ArgKind::Number }], desc: "Выдать ХП", level: 2 },
+        AdminCommand { name: "/sethealthall", aliases: &["/sethpall"], args: &[Arg { name: "HP", kind: ArgKind::Number }], desc: "Выдать ХП всем рядом", level: 2 },
+        AdminCommand { name: "/setarmor", aliases: &[], args: &[PLAYER, Arg { name: "Броня", kind: ArgKind::Number }], desc: "Выдать броню", level: 2 },
+        AdminCommand { name: "/setarmorall", aliases: &[], args: &[Arg { name: "Броня", kind: ArgKind::Number }], desc: "Выдать броню всем рядом", level: 2 },
+        AdminCommand { name: "/freezeall", aliases: &[], args: &[], desc: "Заморозить всех рядом", level: 2 },
+        AdminCommand { name: "/unfreezeall", aliases: &[], args: &[], desc: "Разморозить всех рядом", level: 2 },
+        AdminCommand { name: "/givegun", aliases: &["/gun"], args: &[PLAYER, Arg { name: "Оружие", kind: ArgKind::Text }, Arg { name: "Патроны", kind: ArgKind::Number }], desc: "Выдать оружие", level: 2 },
+        AdminCommand { name: "/givegunall", aliases: &["/gunall"], args: &[Arg { name: "Оружие", kind: ArgKind::Text }, Arg { name: "Патроны", kind: ArgKind::Number }], desc: "Выдать оружие всем рядом", level: 2 },
+        AdminCommand { name: "/model", aliases: &[], args: &[PLAYER, Arg { name: "Скин", kind: ArgKind::Text }], desc: "Выдать скин игроку", level: 2 },
+        AdminCommand { name: "/modelall", aliases: &[], args: &[Arg { name: "Скин", kind: ArgKind::Text }], desc: "Выдать скин всем рядом", level: 2 },
+        AdminCommand { name: "/muteall", aliases: &[], args: &[], desc: "Выдать запрет на голос всем рядом", level: 2 },
+        AdminCommand { name: "/unmuteall", aliases: &[], args: &[], desc: "Снять запрет на голос всем рядом", level: 2 },
+        AdminCommand { name: "/killall", aliases: &[], args: &[], desc: "Убить всех рядом", level: 2 },
+        AdminCommand { name: "/dimension", aliases: &["/dim"], args: &[PLAYER, Arg { name: "Мир", kind: ArgKind::Number }], desc: "ТП в виртуальный мире", level: 3 },
+        AdminCommand { name: "/dimension_close", aliases: &[], args: &[], desc: "Закрыть виртуальный мир", level: 3 },
+        AdminCommand { name: "/gomp", aliases: &[], args: &[Arg { name: "Игроков", kind: ArgKind::Number }, Arg { name: "Время", kind: ArgKind::Number }, Arg { name: "Название", kind: ArgKind::Reason }], desc: "запустить МП", level: 3 },
+        AdminCommand { name: "/gomp_stop", aliases: &[], args: &[], desc: "Закрыть МП", level: 3 },
+        AdminCommand { name: "/unwarn", aliases: &[], args: &[PLAYER, REASON], desc: "Снять последнее предупреждение", level: 3 },
+        AdminCommand { name: "/setleader", aliases: &[], args: &[PLAYER, Arg { name: "Фракция", kind: ArgKind::Text }], desc: "Выдать лидерку", level: 5 },
+        AdminCommand { name: "/unsetleader", aliases: &[], args: &[PLAYER], desc: "Снять лидерку", level: 5 },
+        AdminCommand { name: "/setfractionrank", aliases: &["/setfactionrank"], args: &[PLAYER, Arg { name: "Фракция", kind: ArgKind::Text }, Arg { name: "Ранг", kind: ArgKind::Number }], desc: "Установить ранг во фракции", level: 5 },
+        AdminCommand { name: "/setfraction", aliases: &[], args: &[PLAYER, Arg { name: "Фракция", kind: ArgKind::Text }], desc: "Установить фракцию", level: 5 },
+        AdminCommand { name: "/getfractionname", aliases: &[], args: &[], desc: "Узнать ID фракций", level: 5 },
+        AdminCommand { name: "/forbid_buy_chips", aliases: &[], args: &[], desc: "Запрет на покупку фишек", level: 5 },
+        AdminCommand { name: "/unforbid_buy_chips", aliases: &[], args: &[], desc: "Снять запрет на покупку фишек", level: 5 },
+        AdminCommand { name: "/greenzoneoff", aliases: &[], args: &[], desc: "Выключить ЗЗ", level: 5 },
+        AdminCommand { name: "/greenzoneon", aliases: &[], args: &[], desc: "Включить ЗЗ", level: 5 },
+        AdminCommand { name: "/setstaff", aliases: &[], args: &[PLAYER, Arg { name: "Ранг", kind: ArgKind::Number }], desc: "Выдать админ ранг", level: 5 },
+        AdminCommand { name: "/prolongedban", aliases: &["/pban"], args: &[PLAYER, DURATION, REASON], desc: "Продлить бан более 30 дней", level: 5 },
+        AdminCommand { name: "/strictban", aliases: &["/sban"], args: &[PLAYER, DURATION, REASON], desc: "Выдать бан более 30 дней", level: 5 },
+        AdminCommand { name: "/giveitem", aliases: &[], args: &[], desc: "Выдача предметов", level: 7 },
+        AdminCommand { name: "/changename", aliases: &[], args: &[], desc: "Поменять никнейм", level: 7 },
+        AdminCommand { name: "/wipestorage", aliases: &[], args: &[], desc: "Обнулить склад", level: 7 },
+        AdminCommand { name: "/addcredits", aliases: &[], args: &[], desc: "Выдать донат", level: 7 },
+        AdminCommand { name: "/addmoney", aliases: &[], args: &[], desc: "Выдать деньги", level: 7 },
+        AdminCommand { name: "/givecase", aliases: &[], args: &[], desc: "Выдать кейс", level: 7 },
+        AdminCommand { name: "/perma", aliases: &[], args: &[PLAYER, REASON], desc: "Бесрочный бан на всех серверах", level: 7 },
+        AdminCommand { name: "/wizardreset", aliases: &[], args: &[PLAYER], desc: "сменить пол", level: 7 },
     ]
 }
 
@@ -670,4 +766,47 @@ pub fn get_teleports() -> Vec<Teleport> {
         Teleport { name: "ВЗА 4/5".to_string(), command: "-80, 1265, 130, 155.21".to_string(), category: "ВЗК, ВЗА".to_string() },
         Teleport { name: "ВЗА 5/5".to_string(), command: "1540, 1550, 150, 155.21".to_string(), category: "ВЗК, ВЗА".to_string() },
     ]
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str) -> &'static AdminCommand {
+        admin_commands().iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn search_finds_commands_by_alias() {
+        let adminmode = command("/adminmode");
+        assert!(adminmode.matches("/nc"));
+        assert!(adminmode.matches("NC"));
+        assert!(adminmode.matches("невидимости"));
+        assert!(adminmode.matches(""));
+        assert!(!adminmode.matches("/mc"));
+        assert_eq!(adminmode.matched_alias("/nc"), Some("/nc"));
+        assert_eq!(adminmode.matched_alias("/noc"), Some("/noclip"));
+        // Нашлась по полному имени - сокращение не показываем
+        assert_eq!(adminmode.matched_alias("admin"), None);
+        assert_eq!(adminmode.matched_alias(" "), None);
+    }
+
+    #[test]
+    fn short_form_is_the_shortest_alias() {
+        assert_eq!(command("/adminmode").send_name(true), "/nc");
+        assert_eq!(command("/adminmode").send_name(false), "/adminmode");
+        assert_eq!(command("/mutechat").send_name(true), "/mc");
+        assert_eq!(command("/ban").send_name(true), "/ban");
+    }
+
+    #[test]
+    fn typed_commands_resolve_through_aliases() {
+        assert_eq!(find_command("/nc").map(|c| c.name), Some("/adminmode"));
+        assert_eq!(find_command("/mc 12 30mi мат").map(|c| c.name), Some("/mutechat"));
+        assert_eq!(find_command("/setfactionrank 12 LSPD 3").map(|c| c.name), Some("/setfractionrank"));
+        assert!(find_command("/nosuchcommand").is_none());
+        assert_eq!(command_level("/nosuchcommand"), 0);
+        assert!(check_level("/ban 12 3d мат\n\n/gm", 0).is_ok());
+        assert!(check_level("/gm\n/setfactionrank 12 LSPD 3", 0).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::log;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+
+// ================= ИСТОРИЯ КОМАНД =================
+// Все команды, отправленные в чат из меню, дописываются в history.jsonl.
+// В отличие от логов история переживает перезапуск; хранятся последние HISTORY_LIMIT.
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+const HISTORY_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub time: String,
+    pub command: String,
+    /// Откуда отправлено: "F6", "F7", "МП"...
+    pub origin: String,
+}
+
+static HISTORY: OnceLock<Mutex<Vec<HistoryEntry>>> = OnceLock::new();
+
+fn load_history(path: &str) -> Vec<HistoryEntry> {
+    let Ok(data) = fs::read_to_string(path) else { return Vec::new() };
+    let mut entries: Vec<HistoryEntry> = data.lines().filter_map(|l| serde_json::from_str(l).ok()).collect();
+    // Файл только дополняется, поэтому изредка переписываем его, отрезая старое
+    if entries.len() > HISTORY_LIMIT * 2 {
+        entries.drain(..entries.len() - HISTORY_LIMIT);
+        let text: String = entries.iter().filter_map(|e| serde_json::to_string(e).ok()).map(|l| l + "\n").collect();
+        if let Err(e) = fs::write(path, text) { log(&format!("History: trim failed: {}", e)); }
+    }
+    entries
+}
+
+fn store() -> &'static Mutex<Vec<HistoryEntry>> {
+    HISTORY.get_or_init(|| Mutex::new(load_history(HISTORY_FILE)))
+}
+
+/// Запомнить отправленный текст; многострочный - по команде на строку.
+pub fn record(origin: &str, text: &str) {
+    let time = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let Ok(mut entries) = store().lock() else { return };
+    append(&mut entries, HISTORY_FILE, &time, origin, text);
+}
+
+fn append(entries: &mut Vec<HistoryEntry>, path: &str, time: &str, origin: &str, text: &str) {
+    let mut file = OpenOptions::new().create(true).append(true).open(path);
+    for line in text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        let entry = HistoryEntry { time: time.to_string(), command: line.to_string(), origin: origin.to_string() };
+        if let (Ok(f), Ok(json)) = (file.as_mut(), serde_json::to_string(&entry)) {
+            if let Err(e) = writeln!(f, "{}", json) { log(&format!("History: write failed: {}", e)); }
+        }
+        entries.push(entry);
+    }
+    if entries.len() > HISTORY_LIMIT { let extra = entries.len() - HISTORY_LIMIT; entries.drain(..extra); }
+}
+
+/// Последние команды, новые в начале; `query` ищет по тексту команды и меню.
+pub fn search(query: &str) -> Vec<HistoryEntry> {
+    let Ok(entries) = store().lock() else { return Vec::new() };
+    filter(&entries, query)
+}
+
+fn filter(entries: &[HistoryEntry], query: &str) -> Vec<HistoryEntry> {
+    let query = query.trim().to_lowercase();
+    entries.iter().rev().filter(|e| query.is_empty() || e.command.to_lowercase().contains(&query) || e.origin.to_lowercase().contains(&query)).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Свой файл на тест во временной папке, чтобы не трогать history.jsonl рядом с программой.
+    fn temp_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("admin_helper_history_{}_{}.jsonl", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    fn commands(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.command.as_str()).collect()
+    }
+
+    #[test]
+    fn record_and_load_round_trip() {
+        let path = temp_file("round_trip");
+        let mut entries = Vec::new();
+        append(&mut entries, &path, "2026-10-18 12:00:00", "F6", "/gm");
+        append(&mut entries, &path, "2026-10-18 12:01:00", "МП", " /tp 1 \n\n/ban 2 3d мат ");
+        assert_eq!(commands(&entries), ["/gm", "/tp 1", "/ban 2 3d мат"]);
+        let loaded = load_history(&path);
+        assert_eq!(commands(&loaded), commands(&entries));
+        assert_eq!(loaded.iter().map(|e| e.origin.as_str()).collect::<Vec<_>>(), ["F6", "МП", "МП"]);
+        assert_eq!(loaded[2].time, "2026-10-18 12:01:00");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn history_keeps_last_entries() {
+        let path = temp_file("limit");
+        let mut entries = Vec::new();
+        let text: String = (0..HISTORY_LIMIT * 2 + 10).map(|i| format!("/cmd {}\n", i)).collect();
+        append(&mut entries, &path, "2026-10-18 12:00:00", "F6", &text);
+        assert_eq!(entries.len(), HISTORY_LIMIT);
+        assert_eq!(entries[0].command, format!("/cmd {}", HISTORY_LIMIT + 10));
+        // В файле все строки, пока их не больше двойного лимита; тогда он переписывается
+        let loaded = load_history(&path);
+        assert_eq!(commands(&loaded), commands(&entries));
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), HISTORY_LIMIT);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn search_by_command_and_origin() {
+        let entry = |command: &str, origin: &str| HistoryEntry { time: String::new(), command: command.into(), origin: origin.into() };
+        let entries = [entry("/ban 1 3d", "F7"), entry("/gm", "F6"), entry("/ban 2 3d", "F7 очередь"), entry("/mutechat 3", "F7")];
+        assert_eq!(commands(&filter(&entries, "")), ["/mutechat 3", "/ban 2 3d", "/gm", "/ban 1 3d"]);
+        assert_eq!(commands(&filter(&entries, " /BAN ")), ["/ban 2 3d", "/ban 1 3d"]);
+        assert_eq!(commands(&filter(&entries, "очередь")), ["/ban 2 3d"]);
+        assert_eq!(commands(&filter(&entries, "f6")), ["/gm"]);
+        assert!(filter(&entries, "/kick").is_empty());
+    }
+}
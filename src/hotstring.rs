@@ -0,0 +1,512 @@
+use arboard::Clipboard;
+use chrono::Local;
+use rdev::Key;
+use serde::{Deserialize, Serialize};
+
+use crate::search;
+
+// ================= ПОДСТАНОВКИ В АВТОЗАМЕНАХ =================
+// Текст бинда может содержать {admin_name}, {last_id} и т.п. - они раскрываются
+// в момент срабатывания. Неизвестные {..} печатаются как есть.
+
+pub const PLACEHOLDERS: [(&str, &str); 7] = [
+    ("admin_name", "ваш ник"),
+    ("admin_id", "ваш статический ID"),
+    ("date", "сегодняшняя дата"),
+    ("time", "текущее время"),
+    ("clipboard", "текст из буфера обмена"),
+    ("last_id", "ID из меню наказаний F7"),
+    ("last_article", "статья, выбранная в F7"),
+];
+
+/// То, что знает только окно программы; слушатель клавиатуры читает копию.
+#[derive(Clone, Default)]
+pub struct HotstringContext {
+    pub admin_name: String,
+    pub admin_id: String,
+    pub last_id: String,
+    pub last_article: String,
+}
+
+fn value(name: &str, ctx: &HotstringContext) -> Option<String> {
+    match name {
+        "admin_name" => Some(ctx.admin_name.clone()),
+        "admin_id" => Some(ctx.admin_id.clone()),
+        "date" => Some(Local::now().format("%d.%m.%Y").to_string()),
+        "time" => Some(Local::now().format("%H:%M").to_string()),
+        // Буфер читается до того, как type_in_game положит туда сам текст бинда
+        "clipboard" => Some(Clipboard::new().and_then(|mut c| c.get_text()).unwrap_or_default().trim().to_string()),
+        "last_id" => Some(ctx.last_id.clone()),
+        "last_article" => Some(ctx.last_article.clone()),
+        _ => None,
+    }
+}
+
+pub fn expand(text: &str, ctx: &HotstringContext) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').and_then(|end| value(&after[..end], ctx).map(|v| (end, v))) {
+            Some((end, v)) => { out.push_str(&v); rest = &after[end + 1..]; }
+            None => { out.push('{'); rest = after; }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// ================= ПОЛЯ-ЗАПРОСЫ =================
+// {?ID}, {?Срок} - значения, которые админ вводит при срабатывании бинда
+// в маленьком окне поверх игры. Одинаковые поля спрашиваются один раз.
+
+/// Бинд с полями, ожидающий ввода: текст уже с раскрытыми подстановками.
+pub struct PromptRequest {
+    pub label: String,
+    pub text: String,
+    pub fields: Vec<String>,
+}
+
+pub fn prompt_fields(text: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{?") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else { break };
+        let name = after[..end].to_string();
+        if !name.trim().is_empty() && !fields.contains(&name) { fields.push(name); }
+        rest = &after[end + 1..];
+    }
+    fields
+}
+
+pub fn fill_prompts(text: &str, fields: &[String], values: &[String]) -> String {
+    let mut out = text.to_string();
+    for (name, value) in fields.iter().zip(values) {
+        out = out.replace(&format!("{{?{}}}", name), value.trim());
+    }
+    out
+}
+
+// ================= РАСКЛАДКА И БУФЕР НАБОРА =================
+// rdev отдает символ в раскладке окна игры, поэтому "п1" в английской раскладке
+// приходит как "g1". Буфер помнит и символ, и физическую клавишу: бинд с
+// "любой раскладкой" сравнивается по клавишам, и переключение раскладки
+// посреди набора ему не мешает.
+
+const BUFFER_LIMIT: usize = 30;
+
+/// Настройки срабатывания бинда. Хранятся в config.json вместе с биндом.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BindOptions {
+    /// Срабатывать при наборе тех же клавиш в любой раскладке ("п1" = "g1").
+    pub any_layout: bool,
+    /// Перед триггером должен быть пробел, знак препинания или начало строки:
+    /// "ку1" не сработает внутри "паку1".
+    pub word_start: bool,
+    /// Бинд срабатывает только после пробела или Tab за триггером.
+    pub end_key: bool,
+    /// "П1" и "п1" - разные триггеры.
+    pub case_sensitive: bool,
+    /// Не стирать набранный триггер, текст дописывается после него.
+    pub keep_trigger: bool,
+}
+
+impl Default for BindOptions {
+    fn default() -> Self {
+        Self { any_layout: true, word_start: false, end_key: false, case_sensitive: false, keep_trigger: false }
+    }
+}
+
+/// Символ клавиши в английской раскладке без Shift.
+fn key_char(key: Key) -> Option<char> {
+    let c = match key {
+        Key::KeyA => 'a', Key::KeyB => 'b', Key::KeyC => 'c', Key::KeyD => 'd', Key::KeyE => 'e',
+        Key::KeyF => 'f', Key::KeyG => 'g', Key::KeyH => 'h', Key::KeyI => 'i', Key::KeyJ => 'j',
+        Key::KeyK => 'k', Key::KeyL => 'l', Key::KeyM => 'm', Key::KeyN => 'n', Key::KeyO => 'o',
+        Key::KeyP => 'p', Key::KeyQ => 'q', Key::KeyR => 'r', Key::KeyS => 's', Key::KeyT => 't',
+        Key::KeyU => 'u', Key::KeyV => 'v', Key::KeyW => 'w', Key::KeyX => 'x', Key::KeyY => 'y',
+        Key::KeyZ => 'z',
+        Key::Num0 | Key::Kp0 => '0', Key::Num1 | Key::Kp1 => '1', Key::Num2 | Key::Kp2 => '2',
+        Key::Num3 | Key::Kp3 => '3', Key::Num4 | Key::Kp4 => '4', Key::Num5 | Key::Kp5 => '5',
+        Key::Num6 | Key::Kp6 => '6', Key::Num7 | Key::Kp7 => '7', Key::Num8 | Key::Kp8 => '8',
+        Key::Num9 | Key::Kp9 => '9',
+        Key::BackQuote => '`', Key::Minus => '-', Key::Equal => '=', Key::LeftBracket => '[',
+        Key::RightBracket => ']', Key::SemiColon => ';', Key::Quote => '\'', Key::BackSlash => '\\',
+        Key::Comma => ',', Key::Dot => '.', Key::Slash => '/', Key::Space => ' ', Key::Tab => '\t',
+        _ => return None,
+    };
+    Some(c)
+}
+
+fn is_modifier(key: Key) -> bool {
+    matches!(key, Key::ShiftLeft | Key::ShiftRight | Key::ControlLeft | Key::ControlRight | Key::Alt | Key::AltGr | Key::MetaLeft | Key::MetaRight | Key::CapsLock)
+}
+
+struct Typed {
+    /// Что напечаталось, если rdev это знает.
+    typed: Option<char>,
+    /// Клавиша в английской раскладке.
+    key: Option<char>,
+}
+
+impl Typed {
+    fn char(&self) -> Option<char> {
+        self.typed.or(self.key)
+    }
+
+    fn matches(&self, t: char, opts: &BindOptions) -> bool {
+        let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+        let typed_ok = if opts.case_sensitive { self.typed == Some(t) } else { self.typed.map(lower) == Some(lower(t)) };
+        if typed_ok { return true; }
+        let Some(k) = self.key else { return false };
+        // Клавиша годится, только если напечатан ее обычный символ в одной из раскладок:
+        // Shift+1 дает "!", и бинд "п1" на него срабатывать не должен
+        let unshifted = self.typed.is_none_or(|c| lower(c) == k || lower(c) == search::swap_layout_char(k));
+        // Клавиша регистра не знает - его берем из напечатанного символа, если он есть
+        let case_ok = !opts.case_sensitive || self.typed.is_none_or(|c| c.is_uppercase() == t.is_uppercase());
+        let t = lower(t);
+        opts.any_layout && unshifted && case_ok && (k == t || search::swap_layout_char(k) == t)
+    }
+
+    fn is_separator(&self) -> bool {
+        self.char().is_some_and(|c| c.is_whitespace() || c.is_ascii_punctuation())
+    }
+}
+
+#[derive(Default)]
+pub struct KeyBuffer {
+    chars: Vec<Typed>,
+    /// Зажаты Ctrl/Alt/Win: Ctrl+V, Alt+Shift и т.п. не попадают в буфер.
+    held: Vec<Key>,
+    /// Начало строки уже вытеснено из буфера.
+    trimmed: bool,
+}
+
+impl KeyBuffer {
+    pub fn press(&mut self, key: Key, name: Option<&str>) {
+        match key {
+            Key::Return | Key::KpReturn | Key::Escape => return self.clear(),
+            // Ctrl+Backspace стирает слово целиком - проще начать заново
+            Key::Backspace => { if self.held.is_empty() { self.chars.pop(); } else { self.clear(); } return; }
+            _ if is_modifier(key) => {
+                if !matches!(key, Key::ShiftLeft | Key::ShiftRight | Key::CapsLock | Key::AltGr) && !self.held.contains(&key) { self.held.push(key); }
+                return;
+            }
+            _ if !self.held.is_empty() => return,
+            _ => {}
+        }
+        let typed: Vec<char> = name.unwrap_or("").chars().filter(|c| !c.is_control()).collect();
+        let physical = key_char(key);
+        if typed.is_empty() && physical.is_none() { return; }
+        // Мертвая клавиша может дать два символа - клавиша относится к последнему
+        let last = typed.len().saturating_sub(1);
+        if typed.is_empty() {
+            self.chars.push(Typed { typed: None, key: physical });
+        }
+        for (i, c) in typed.into_iter().enumerate() {
+            self.chars.push(Typed { typed: Some(c), key: if i == last { physical } else { None } });
+        }
+        if self.chars.len() > BUFFER_LIMIT {
+            self.chars.drain(..self.chars.len() - BUFFER_LIMIT);
+            self.trimmed = true;
+        }
+    }
+
+    pub fn release(&mut self, key: Key) {
+        self.held.retain(|k| *k != key);
+    }
+
+    /// Enter, Esc, смена окна: заодно забываем зажатые модификаторы - после Win+L
+    /// или Ctrl+Alt+Del их отпускание до слушателя не доходит.
+    pub fn clear(&mut self) {
+        self.chars.clear();
+        self.held.clear();
+        self.trimmed = false;
+    }
+
+    /// Сработал ли бинд на последнем нажатии. Возвращает, сколько символов
+    /// стереть перед вставкой текста (триггер и пробел-завершение).
+    pub fn check(&self, trigger: &str, opts: &BindOptions) -> Option<usize> {
+        let trigger: Vec<char> = trigger.chars().collect();
+        if trigger.is_empty() { return None; }
+        let mut end = self.chars.len();
+        let mut ending = 0;
+        if opts.end_key {
+            // Tab в чате ничего не печатает, а пробел стираем вместе с триггером
+            match self.chars.last().and_then(Typed::char) {
+                Some(' ') => ending = 1,
+                Some('\t') => {}
+                _ => return None,
+            }
+            end -= 1;
+        }
+        if trigger.len() > end { return None; }
+        let start = end - trigger.len();
+        if !self.chars[start..end].iter().zip(&trigger).all(|(typed, &t)| typed.matches(t, opts)) { return None; }
+        if opts.word_start {
+            let separated = if start == 0 { !self.trimmed } else { self.chars[start - 1].is_separator() };
+            if !separated { return None; }
+        }
+        Some(if opts.keep_trigger { 0 } else { trigger.len() + ending })
+    }
+}
+
+// ================= КОНФЛИКТЫ БИНДОВ =================
+// Слушатель берет первый подошедший бинд по списку, поэтому бинд, который
+// совпадает с другим или содержит его, может никогда не сработать.
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConflictKind {
+    /// Одинаковые триггеры.
+    Duplicate,
+    /// Короткий триггер - конец длинного: на одном нажатии срабатывает короткий.
+    Suffix,
+    /// Короткий триггер набирается раньше, чем длинный закончится.
+    Inside,
+}
+
+impl ConflictKind {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ConflictKind::Duplicate => "такой же бинд",
+            ConflictKind::Suffix => "срабатывает на том же нажатии",
+            ConflictKind::Inside => "срабатывает раньше, пока набирается этот",
+        }
+    }
+}
+
+/// `shadowed` не сработает, потому что раньше сработает `by` (индексы в списке биндов).
+#[derive(Clone, Copy, Debug)]
+pub struct TriggerConflict {
+    pub shadowed: usize,
+    pub by: usize,
+    pub kind: ConflictKind,
+}
+
+/// Триггер в том виде, в каком его сравнивает слушатель: без регистра и/или раскладки.
+fn fold(trigger: &str, fold_case: bool, fold_layout: bool) -> Vec<char> {
+    trigger.chars().map(|c| {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let key = if fold_layout && !lower.is_ascii() { search::swap_layout_char(lower) } else { lower };
+        if !fold_case && c.is_uppercase() { key.to_uppercase().next().unwrap_or(key) } else { key }
+    }).collect()
+}
+
+/// Срабатывает ли короткий бинд, пока набирается длинный.
+fn fires_inside(short: &[char], short_opts: &BindOptions, long: &[char], long_raw: &[char], long_opts: &BindOptions) -> Option<ConflictKind> {
+    if short.len() > long.len() { return None; }
+    let is_separator = |c: char| c.is_whitespace() || c.is_ascii_punctuation();
+    for p in 0..=long.len() - short.len() {
+        if long[p..p + short.len()] != *short { continue; }
+        if short_opts.word_start && p > 0 && !is_separator(long_raw[p - 1]) { continue; }
+        let end = p + short.len();
+        if end == long.len() {
+            // Короткому нужен пробел после триггера, а длинный сработает без него
+            if short_opts.end_key && !long_opts.end_key { continue; }
+            return Some(if short.len() == long.len() { ConflictKind::Duplicate } else { ConflictKind::Suffix });
+        }
+        if short_opts.end_key && !matches!(long_raw[end], ' ' | '\t') { continue; }
+        return Some(ConflictKind::Inside);
+    }
+    None
+}
+
+pub fn find_conflicts(binds: &[(&str, &BindOptions)]) -> Vec<TriggerConflict> {
+    let mut conflicts = Vec::new();
+    for (a, (trigger_a, opts_a)) in binds.iter().enumerate() {
+        if trigger_a.is_empty() { continue; }
+        for (b, (trigger_b, opts_b)) in binds.iter().enumerate().skip(a + 1) {
+            if trigger_b.is_empty() { continue; }
+            let fold_case = !opts_a.case_sensitive || !opts_b.case_sensitive;
+            let fold_layout = opts_a.any_layout || opts_b.any_layout;
+            let (fa, fb) = (fold(trigger_a, fold_case, fold_layout), fold(trigger_b, fold_case, fold_layout));
+            let (ra, rb): (Vec<char>, Vec<char>) = (trigger_a.chars().collect(), trigger_b.chars().collect());
+            // Короткий триггер забирает длинный независимо от порядка в списке
+            // (слушатель на одном нажатии выбирает самый короткий), а из двух
+            // одинаковых срабатывает тот, что выше
+            if let Some(kind) = fires_inside(&fa, opts_a, &fb, &rb, opts_b) {
+                conflicts.push(TriggerConflict { shadowed: b, by: a, kind });
+            } else if let Some(kind) = fires_inside(&fb, opts_b, &fa, &ra, opts_a) {
+                let (shadowed, by) = if kind == ConflictKind::Duplicate { (b, a) } else { (a, b) };
+                conflicts.push(TriggerConflict { shadowed, by, kind });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_known_placeholders_only() {
+        let ctx = HotstringContext { admin_name: "Admin_Name".into(), last_id: "12".into(), ..HotstringContext::default() };
+        assert_eq!(expand("Я {admin_name}, ID {last_id}", &ctx), "Я Admin_Name, ID 12");
+        assert_eq!(expand("{last_article}|", &ctx), "|");
+        // Чужие скобки и поля-запросы остаются как есть
+        assert_eq!(expand("{nope} {?ID} {last_id", &ctx), "{nope} {?ID} {last_id");
+        assert_eq!(expand("{{last_id}}", &ctx), "{12}");
+    }
+
+    #[test]
+    fn prompt_fields_asked_once_and_filled() {
+        let text = "/ban {?ID} {?Срок} | {?ID} {? } {?}";
+        let fields = prompt_fields(text);
+        assert_eq!(fields, ["ID", "Срок"]);
+        assert_eq!(fill_prompts(text, &fields, &[" 12 ".into(), "3d".into()]), "/ban 12 3d | 12 {? } {?}");
+        assert!(prompt_fields("{?ID").is_empty());
+    }
+
+    fn typed(keys: &[(Key, &str)]) -> KeyBuffer {
+        let mut buffer = KeyBuffer::default();
+        for (key, name) in keys { buffer.press(*key, if name.is_empty() { None } else { Some(name) }); }
+        buffer
+    }
+
+    #[test]
+    fn any_layout_matches_same_keys() {
+        let exact = BindOptions { any_layout: false, ..BindOptions::default() };
+        let buffer = typed(&[(Key::KeyG, "g"), (Key::Num1, "1")]);
+        assert_eq!(buffer.check("п1", &BindOptions::default()), Some(2));
+        assert_eq!(buffer.check("п1", &exact), None);
+        // Переключение раскладки посреди набора
+        let mut buffer = typed(&[(Key::KeyG, "g"), (Key::Alt, ""), (Key::ShiftLeft, "")]);
+        buffer.release(Key::Alt);
+        buffer.press(Key::Num1, Some("1"));
+        assert_eq!(buffer.check("п1", &BindOptions::default()), Some(2));
+    }
+
+    #[test]
+    fn shifted_keys_do_not_match_unshifted_trigger() {
+        let buffer = typed(&[(Key::KeyG, "п"), (Key::ShiftLeft, ""), (Key::Num1, "!")]);
+        assert_eq!(buffer.check("п1", &BindOptions::default()), None);
+        assert_eq!(buffer.check("п!", &BindOptions::default()), Some(2));
+        // В русской раскладке Shift+1 тоже "!", а "." на клавише "/"
+        let buffer = typed(&[(Key::KeyG, "G"), (Key::Num1, "1")]);
+        assert_eq!(buffer.check("п1", &BindOptions::default()), Some(2));
+        let buffer = typed(&[(Key::KeyG, "п"), (Key::Slash, ".")]);
+        assert_eq!(buffer.check("п.", &BindOptions::default()), Some(2));
+    }
+
+    #[test]
+    fn ctrl_combinations_stay_out_of_buffer() {
+        let mut buffer = typed(&[(Key::KeyG, "п"), (Key::ControlLeft, ""), (Key::KeyV, "\u{16}")]);
+        buffer.release(Key::ControlLeft);
+        buffer.press(Key::Num1, Some("1"));
+        assert_eq!(buffer.check("п1", &BindOptions::default()), Some(2));
+    }
+
+    fn text(s: &str) -> KeyBuffer {
+        let mut buffer = KeyBuffer::default();
+        for c in s.chars() {
+            let key = match c { ' ' => Key::Space, '\t' => Key::Tab, _ => Key::Unknown(0) };
+            let name = c.to_string();
+            buffer.press(key, if c == '\t' { None } else { Some(&name) });
+        }
+        buffer
+    }
+
+    #[test]
+    fn word_start_needs_separator_before_trigger() {
+        let word = BindOptions { word_start: true, ..BindOptions::default() };
+        assert_eq!(text("ку1").check("ку1", &word), Some(3));
+        assert_eq!(text("привет, ку1").check("ку1", &word), Some(3));
+        assert_eq!(text("паку1").check("ку1", &word), None);
+        assert_eq!(text("паку1").check("ку1", &BindOptions::default()), Some(3));
+        // Начало строки вытеснено из буфера - неизвестно, что было перед триггером
+        let long = text(&format!("{}ку1", "а".repeat(BUFFER_LIMIT)));
+        assert_eq!(long.check("ку1", &word), None);
+        assert_eq!(long.check("ку1", &BindOptions::default()), Some(3));
+    }
+
+    #[test]
+    fn end_key_erases_space_but_not_tab() {
+        let end = BindOptions { end_key: true, ..BindOptions::default() };
+        assert_eq!(text("п1").check("п1", &end), None);
+        assert_eq!(text("п1 ").check("п1", &end), Some(3));
+        assert_eq!(text("п1\t").check("п1", &end), Some(2));
+        assert_eq!(text("п1 ").check("п1", &BindOptions::default()), None);
+        let keep = BindOptions { keep_trigger: true, ..end };
+        assert_eq!(text("п1 ").check("п1", &keep), Some(0));
+    }
+
+    #[test]
+    fn case_sensitive_and_keep_trigger() {
+        let case = BindOptions { case_sensitive: true, ..BindOptions::default() };
+        assert_eq!(text("П1").check("п1", &BindOptions::default()), Some(2));
+        assert_eq!(text("П1").check("п1", &case), None);
+        assert_eq!(text("П1").check("П1", &case), Some(2));
+        // Без напечатанного символа регистр берется из триггера
+        let buffer = typed(&[(Key::KeyG, ""), (Key::Num1, "")]);
+        assert_eq!(buffer.check("g1", &case), Some(2));
+        let keep = BindOptions { keep_trigger: true, ..BindOptions::default() };
+        assert_eq!(text("п1").check("п1", &keep), Some(0));
+        assert_eq!(text("п1").check("", &BindOptions::default()), None);
+    }
+
+    #[test]
+    fn clear_forgets_modifier_without_release() {
+        // Win+L: отпускание Win слушатель уже не видит
+        let mut buffer = typed(&[(Key::MetaLeft, ""), (Key::KeyL, "l")]);
+        buffer.press(Key::KeyG, Some("п"));
+        assert_eq!(buffer.check("п", &BindOptions::default()), None);
+        let mut buffer = typed(&[(Key::ControlLeft, "")]);
+        buffer.clear();
+        buffer.press(Key::KeyG, Some("п"));
+        buffer.press(Key::Num1, Some("1"));
+        assert_eq!(buffer.check("п1", &BindOptions::default()), Some(2));
+        let mut buffer = typed(&[(Key::Alt, ""), (Key::Return, "")]);
+        buffer.press(Key::KeyG, Some("п"));
+        buffer.press(Key::Num1, Some("1"));
+        assert_eq!(buffer.check("п1", &BindOptions::default()), Some(2));
+    }
+
+    fn conflicts(binds: &[(&str, BindOptions)]) -> Vec<(usize, usize, ConflictKind)> {
+        let binds: Vec<(&str, &BindOptions)> = binds.iter().map(|(t, o)| (*t, o)).collect();
+        find_conflicts(&binds).into_iter().map(|c| (c.shadowed, c.by, c.kind)).collect()
+    }
+
+    #[test]
+    fn suffix_shadows_long_trigger_in_both_orders() {
+        let d = BindOptions::default;
+        assert_eq!(conflicts(&[("кп1", d()), ("п1", d())]), vec![(0, 1, ConflictKind::Suffix)]);
+        assert_eq!(conflicts(&[("п1", d()), ("кп1", d())]), vec![(1, 0, ConflictKind::Suffix)]);
+    }
+
+    #[test]
+    fn short_prefix_fires_before_long_trigger() {
+        let d = BindOptions::default;
+        assert_eq!(conflicts(&[("п1к", d()), ("п1", d())]), vec![(0, 1, ConflictKind::Inside)]);
+        assert_eq!(conflicts(&[("п1", d()), ("п1к", d())]), vec![(1, 0, ConflictKind::Inside)]);
+    }
+
+    #[test]
+    fn duplicates_across_layouts_and_case() {
+        let d = BindOptions::default;
+        let exact = || BindOptions { any_layout: false, ..BindOptions::default() };
+        let case = || BindOptions { case_sensitive: true, ..BindOptions::default() };
+        assert_eq!(conflicts(&[("п1", d()), ("g1", d())]), vec![(1, 0, ConflictKind::Duplicate)]);
+        assert_eq!(conflicts(&[("п1", exact()), ("g1", d())]), vec![(1, 0, ConflictKind::Duplicate)]);
+        assert!(conflicts(&[("п1", exact()), ("g1", exact())]).is_empty());
+        assert_eq!(conflicts(&[("п1", d()), ("П1", d())]), vec![(1, 0, ConflictKind::Duplicate)]);
+        assert!(conflicts(&[("п1", case()), ("П1", case())]).is_empty());
+    }
+
+    #[test]
+    fn word_start_and_end_key_avoid_conflicts() {
+        let d = BindOptions::default;
+        let word = || BindOptions { word_start: true, ..BindOptions::default() };
+        let end = || BindOptions { end_key: true, ..BindOptions::default() };
+        assert!(conflicts(&[("ку1", word()), ("паку1", d())]).is_empty());
+        assert!(conflicts(&[("паку1", d()), ("ку1", word())]).is_empty());
+        // В начале длинного триггера слово начинается там же
+        assert_eq!(conflicts(&[("ку", word()), ("ку1", d())]), vec![(1, 0, ConflictKind::Inside)]);
+        assert!(conflicts(&[("п", end()), ("п1", d())]).is_empty());
+        assert_eq!(conflicts(&[("п", end()), ("п к", d())]), vec![(1, 0, ConflictKind::Inside)]);
+        assert!(conflicts(&[("", d()), ("", d()), ("а", d())]).is_empty());
+    }
+}
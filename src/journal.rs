@@ -0,0 +1,264 @@
+use crate::log;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+// ================= ЖУРНАЛ НАКАЗАНИЙ =================
+// Каждая выданная через F7 команда дописывается строкой JSON в journal.jsonl.
+// Файл только дополняется, поэтому сбой посреди записи портит максимум одну строку.
+
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub time: String,
+    pub player_id: String,
+    pub article: String,
+    #[serde(default)] pub category: String,
+    /// Тип наказания как в F7: "Ban", "Demorgan", "Warn"...
+    pub punishment: String,
+    #[serde(default)] pub duration: String,
+    #[serde(default)] pub report: String,
+    pub admin: String,
+    #[serde(default)] pub command: String,
+    /// Время наказания, которое снимает эта запись (пусто - обычное наказание)
+    #[serde(default)] pub revokes: String,
+    /// Команда снятого наказания: /ban и /warn одной комбинации выдаются в одну секунду
+    #[serde(default)] pub revokes_command: String,
+}
+
+impl JournalEntry {
+    pub fn stamp_now(&mut self) {
+        self.time = Local::now().format(TIME_FORMAT).to_string();
+    }
+
+    fn parsed_time(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.time, TIME_FORMAT).ok()
+    }
+
+    fn is_on(&self, day: NaiveDate) -> bool {
+        self.time.starts_with(&day.format("%Y-%m-%d").to_string())
+    }
+
+    /// Запись о снятии этого наказания.
+    pub fn revocation(&self, command: String, reason: &str, admin: &str) -> JournalEntry {
+        JournalEntry { player_id: self.player_id.clone(), article: self.article.clone(), category: self.category.clone(), punishment: "Снятие".to_string(), report: reason.to_string(), admin: admin.to_string(), command, revokes: self.time.clone(), revokes_command: self.command.clone(), ..Default::default() }
+    }
+
+    /// "Ban 3d", "Warn", "Ban навсегда"
+    pub fn punishment_text(&self) -> String {
+        if self.duration.is_empty() { self.punishment.clone() } else { format!("{} {}", self.punishment, self.duration) }
+    }
+}
+
+pub fn load_journal() -> Vec<JournalEntry> {
+    let Ok(data) = fs::read_to_string(JOURNAL_FILE) else { return Vec::new() };
+    let mut broken = 0;
+    let entries: Vec<JournalEntry> = data
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).map_err(|_| broken += 1).ok())
+        .collect();
+    if broken > 0 { log(&format!("Journal: skipped {} broken lines in {}", broken, JOURNAL_FILE)); }
+    entries
+}
+
+pub fn append_entry(entry: &JournalEntry) {
+    let Ok(line) = serde_json::to_string(entry) else { return };
+    let result = OpenOptions::new().create(true).append(true).open(JOURNAL_FILE).and_then(|mut f| writeln!(f, "{}", line));
+    if let Err(e) = result { log(&format!("Journal: write failed: {}", e)); }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum JournalPeriod { Today, Week, All }
+
+impl JournalPeriod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JournalPeriod::Today => "Сегодня",
+            JournalPeriod::Week => "7 дней",
+            JournalPeriod::All => "Все время",
+        }
+    }
+}
+
+pub struct JournalFilter {
+    pub player_id: String,
+    pub article: String,
+    pub period: JournalPeriod,
+}
+
+impl Default for JournalFilter {
+    fn default() -> Self {
+        Self { player_id: String::new(), article: String::new(), period: JournalPeriod::Today }
+    }
+}
+
+impl JournalFilter {
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        self.matches_at(entry, Local::now().naive_local())
+    }
+
+    fn matches_at(&self, entry: &JournalEntry, now: NaiveDateTime) -> bool {
+        let id = self.player_id.trim();
+        if !id.is_empty() && entry.player_id != id { return false; }
+        let article = self.article.trim().to_lowercase();
+        if !article.is_empty() && !entry.article.to_lowercase().starts_with(&article) { return false; }
+        match self.period {
+            JournalPeriod::Today => entry.is_on(now.date()),
+            JournalPeriod::Week => entry.parsed_time().map(|t| t >= now - Duration::days(7)).unwrap_or(false),
+            JournalPeriod::All => true,
+        }
+    }
+}
+
+/// Старые записи о снятии без команды снимают все наказания с тем же временем, игроком и статьей.
+pub fn is_revoked(entries: &[JournalEntry], entry: &JournalEntry) -> bool {
+    !entry.time.is_empty() && entries.iter().any(|e| e.revokes == entry.time && e.player_id == entry.player_id && e.article == entry.article && (e.revokes_command.is_empty() || e.revokes_command == entry.command))
+}
+
+/// Причина снятия: ошибка админа или обжалование с номером.
+pub fn revoke_reason(article: &str, appeal: &str) -> String {
+    let appeal = appeal.trim();
+    if appeal.is_empty() { return format!("Ошибочная выдача ({})", article); }
+    let number = if appeal.contains('№') { appeal.to_string() } else { format!("№{}", appeal) };
+    format!("Обжалование {} ({})", number, article)
+}
+
+/// Уже выданные сегодня наказания этому игроку по этой статье.
+pub fn punished_today<'a>(entries: &'a [JournalEntry], player_id: &str, article: &str) -> Vec<&'a JournalEntry> {
+    punished_on(entries, player_id, article, Local::now().date_naive())
+}
+
+fn punished_on<'a>(entries: &'a [JournalEntry], player_id: &str, article: &str, day: NaiveDate) -> Vec<&'a JournalEntry> {
+    if player_id.is_empty() { return Vec::new(); }
+    entries.iter().filter(|e| e.player_id == player_id && e.article == article && e.is_on(day) && e.revokes.is_empty() && !is_revoked(entries, e)).collect()
+}
+
+/// Текстовый отчет для отправки после смены.
+pub fn shift_report(entries: &[&JournalEntry], admin: &str, period: JournalPeriod) -> String {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for e in entries {
+        match counts.iter_mut().find(|(p, _)| *p == e.punishment) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((e.punishment.clone(), 1)),
+        }
+    }
+    let summary: Vec<String> = counts.iter().map(|(p, n)| format!("{}: {}", p, n)).collect();
+
+    let mut report = format!("Отчет администратора {} ({}, {})\n", admin, period.label().to_lowercase(), Local::now().format("%d.%m.%Y"));
+    report.push_str(&format!("Всего наказаний: {}{}\n", entries.len(), if summary.is_empty() { String::new() } else { format!(" ({})", summary.join(", ")) }));
+    for e in entries {
+        let report_num = if e.report.is_empty() { String::new() } else { format!(" | {}", e.report) };
+        report.push_str(&format!("{} | ID {} | {} | {}{}\n", e.time, e.player_id, e.article, e.punishment_text(), report_num));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: &str, player_id: &str, article: &str, punishment: &str) -> JournalEntry {
+        JournalEntry { time: time.into(), player_id: player_id.into(), article: article.into(), punishment: punishment.into(), admin: "Admin".into(), ..Default::default() }
+    }
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, TIME_FORMAT).unwrap()
+    }
+
+    fn filter(player_id: &str, article: &str, period: JournalPeriod) -> JournalFilter {
+        JournalFilter { player_id: player_id.into(), article: article.into(), period }
+    }
+
+    fn issued(time: &str, punishment: &str, command: &str) -> JournalEntry {
+        JournalEntry { command: command.into(), ..entry(time, "12", "3.2 ПП", punishment) }
+    }
+
+    #[test]
+    fn revocation_matches_only_its_command() {
+        let ban = issued("2026-10-18 12:00:00", "Ban", "/ban 12 3d 3.2 ПП");
+        let warn = issued("2026-10-18 12:00:00", "Warn", "/warn 12 3.2 ПП");
+        let other = JournalEntry { player_id: "15".into(), ..ban.clone() };
+        let unban = ban.revocation("/unban 12 Ошибочная выдача (3.2 ПП)".into(), "Ошибочная выдача (3.2 ПП)", "Admin");
+        assert_eq!((unban.punishment.as_str(), unban.revokes.as_str()), ("Снятие", "2026-10-18 12:00:00"));
+        let entries = [ban.clone(), warn.clone(), other.clone(), unban];
+        assert!(is_revoked(&entries, &ban));
+        assert!(!is_revoked(&entries, &warn));
+        assert!(!is_revoked(&entries, &other));
+        // Запись о снятии без команды (из старого журнала) снимает все с тем же временем
+        let legacy = JournalEntry { revokes_command: String::new(), ..ban.revocation(String::new(), "", "Admin") };
+        assert!(is_revoked(&[warn.clone(), legacy], &warn));
+        assert!(!is_revoked(&[], &JournalEntry::default()));
+    }
+
+    #[test]
+    fn revoke_reason_with_and_without_appeal() {
+        assert_eq!(revoke_reason("3.2 ПП", " "), "Ошибочная выдача (3.2 ПП)");
+        assert_eq!(revoke_reason("3.2 ПП", "1234"), "Обжалование №1234 (3.2 ПП)");
+        assert_eq!(revoke_reason("3.2 ПП", "№1234"), "Обжалование №1234 (3.2 ПП)");
+    }
+
+    #[test]
+    fn shift_report_counts_and_lists() {
+        let ban = JournalEntry { duration: "3d".into(), report: "№7".into(), ..issued("2026-10-18 12:00:00", "Ban", "") };
+        let warn = issued("2026-10-18 12:05:00", "Warn", "");
+        let ban2 = JournalEntry { player_id: "15".into(), duration: "7d".into(), ..issued("2026-10-18 12:10:00", "Ban", "") };
+        let report = shift_report(&[&ban, &warn, &ban2], "Admin", JournalPeriod::Today);
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("Отчет администратора Admin (сегодня, "));
+        assert_eq!(lines[1..], [
+            "Всего наказаний: 3 (Ban: 2, Warn: 1)",
+            "2026-10-18 12:00:00 | ID 12 | 3.2 ПП | Ban 3d | №7",
+            "2026-10-18 12:05:00 | ID 12 | 3.2 ПП | Warn",
+            "2026-10-18 12:10:00 | ID 15 | 3.2 ПП | Ban 7d",
+        ]);
+        assert_eq!(shift_report(&[], "Admin", JournalPeriod::All).lines().nth(1), Some("Всего наказаний: 0"));
+    }
+
+    #[test]
+    fn filter_by_period() {
+        let now = at("2026-10-18 00:30:00");
+        let late = entry("2026-10-17 23:59:59", "12", "3.2 ПП", "Ban");
+        let midnight = entry("2026-10-18 00:00:00", "12", "3.2 ПП", "Ban");
+        let old = entry("2026-10-11 00:29:59", "12", "3.2 ПП", "Ban");
+        let today = filter("", "", JournalPeriod::Today);
+        assert!(!today.matches_at(&late, now));
+        assert!(today.matches_at(&midnight, now));
+        let week = filter("", "", JournalPeriod::Week);
+        assert!(week.matches_at(&late, now));
+        assert!(!week.matches_at(&old, now));
+        assert!(week.matches_at(&entry("2026-10-11 00:30:00", "12", "3.2 ПП", "Ban"), now));
+        assert!(!week.matches_at(&entry("вчера", "12", "3.2 ПП", "Ban"), now));
+        assert!(filter("", "", JournalPeriod::All).matches_at(&old, now));
+    }
+
+    #[test]
+    fn filter_by_player_and_article() {
+        let now = at("2026-10-18 12:00:00");
+        let e = entry("2026-10-18 11:00:00", "12", "3.2 ПП", "Ban");
+        assert!(filter(" 12 ", "", JournalPeriod::All).matches_at(&e, now));
+        assert!(!filter("1", "", JournalPeriod::All).matches_at(&e, now));
+        assert!(!filter("123", "", JournalPeriod::All).matches_at(&e, now));
+        assert!(filter("", "3.2 пп", JournalPeriod::All).matches_at(&e, now));
+        assert!(filter("", "3.", JournalPeriod::All).matches_at(&e, now));
+        assert!(!filter("", "2 ПП", JournalPeriod::All).matches_at(&e, now));
+    }
+
+    #[test]
+    fn punished_today_resets_at_midnight() {
+        let entries = [
+            entry("2026-10-17 23:59:59", "12", "3.2 ПП", "Ban"),
+            entry("2026-10-18 00:00:00", "12", "3.2 ПП", "Warn"),
+            entry("2026-10-18 10:00:00", "12", "1.1 ПП", "Ban"),
+            entry("2026-10-18 11:00:00", "15", "3.2 ПП", "Ban"),
+        ];
+        let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        let times = |day: NaiveDate| punished_on(&entries, "12", "3.2 ПП", day).iter().map(|e| e.time.clone()).collect::<Vec<_>>();
+        assert_eq!(times(day("2026-10-17")), ["2026-10-17 23:59:59"]);
+        assert_eq!(times(day("2026-10-18")), ["2026-10-18 00:00:00"]);
+        assert!(punished_on(&entries, "", "3.2 ПП", day("2026-10-18")).is_empty());
+    }
+}
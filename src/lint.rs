@@ -0,0 +1,181 @@
+use crate::rules::{self, article_suffix, RuleRefs, RulesPack};
+use crate::{punish, Rule};
+use std::collections::HashMap;
+use std::fs;
+
+// ================= ПРОВЕРКА ПАКЕТА ПРАВИЛ =================
+// admin_helper --check-rules [путь] [--strict] - проверка rules.json без запуска окна.
+// Код выхода 0 - можно выпускать, 1 - есть ошибки (с --strict - и предупреждения).
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Severity { Error, Warning }
+
+pub struct LintIssue {
+    pub severity: Severity,
+    pub location: String,
+    pub message: String,
+}
+
+fn location(index: usize, rule: &Rule) -> String {
+    format!("#{} {} [{}]", index + 1, rule.article, rule.category)
+}
+
+fn normalize_category(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn check_escapes(text: &str, field: &str, issues: &mut Vec<String>) {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' { issues.push(format!("{}: настоящий перевод строки вместо `n", field)); }
+        if c != '`' { continue; }
+        match chars.peek() {
+            Some('n') if field == "описание" => { chars.next(); }
+            Some('n') => { chars.next(); issues.push(format!("{}: `n здесь не превращается в новую строку", field)); }
+            Some(other) => issues.push(format!("{}: неизвестная экранировка '`{}' (U+{:04X})", field, other, *other as u32)),
+            None => issues.push(format!("{}: ` в конце текста", field)),
+        }
+    }
+}
+
+pub fn lint_pack(pack: &RulesPack) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut push = |severity, location: String, message: String| issues.push(LintIssue { severity, location, message });
+
+    if pack.version.is_empty() { push(Severity::Warning, "пакет".to_string(), "нет версии (поле version) - не будет видно, что изменилось".to_string()); }
+
+    let refs = RuleRefs::build(&pack.rules);
+    let mut first_seen: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut category_spellings: HashMap<String, Vec<&str>> = HashMap::new();
+    for (i, rule) in pack.rules.iter().enumerate() {
+        let loc = location(i, rule);
+        if rule.article.trim().is_empty() { push(Severity::Error, loc.clone(), "пустой номер статьи".to_string()); }
+        if rule.title.trim().is_empty() { push(Severity::Error, loc.clone(), "пустое название".to_string()); }
+        if rule.category.trim().is_empty() { push(Severity::Warning, loc.clone(), "нет категории".to_string()); }
+        if rule.category != rule.category.trim() { push(Severity::Warning, loc.clone(), "пробелы по краям категории".to_string()); }
+
+        // validate_rules начинает строку с номера статьи - он уже есть в location
+        for e in punish::validate_rules(std::slice::from_ref(rule)) { push(Severity::Error, loc.clone(), e.trim_start_matches(rule.article.as_str()).trim_start().to_string()); }
+
+        let mut escapes = Vec::new();
+        check_escapes(&rule.description, "описание", &mut escapes);
+        check_escapes(&rule.title, "название", &mut escapes);
+        check_escapes(&rule.article, "номер", &mut escapes);
+        for e in escapes { push(Severity::Warning, loc.clone(), e); }
+        for r in refs.broken(&pack.rules, i) { push(Severity::Warning, loc.clone(), format!("ссылка на несуществующую статью {}", r)); }
+
+        match first_seen.get(&(rule.category.as_str(), rule.article.as_str())) {
+            Some(&j) => {
+                let same = serde_json::to_value(rule).ok() == serde_json::to_value(&pack.rules[j]).ok();
+                if same {
+                    push(Severity::Warning, loc.clone(), format!("точный повтор статьи #{}, можно удалить", j + 1));
+                } else {
+                    push(Severity::Error, loc.clone(), format!("номер уже занят статьей #{} с другим текстом или сроками", j + 1));
+                }
+            }
+            None => { first_seen.insert((rule.category.as_str(), rule.article.as_str()), i); }
+        }
+
+        let spellings = category_spellings.entry(normalize_category(&rule.category)).or_default();
+        if !spellings.contains(&rule.category.as_str()) { spellings.push(&rule.category); }
+    }
+
+    for spellings in category_spellings.values().filter(|s| s.len() > 1) {
+        let list: Vec<String> = spellings.iter().map(|s| format!("'{}'", s)).collect();
+        push(Severity::Error, "категории".to_string(), format!("одна категория записана по-разному: {}", list.join(", ")));
+    }
+
+    // Сокращение в номере ("ПП", "ПВ") должно быть одним на категорию
+    let tree = rules::category_tree(&pack.rules);
+    for category in &tree {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for &i in &category.rules {
+            let suffix = article_suffix(&pack.rules[i].article);
+            match counts.iter_mut().find(|(s, _)| *s == suffix) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((suffix, 1)),
+            }
+        }
+        let Some(&(main, _)) = counts.iter().max_by_key(|(_, n)| *n) else { continue };
+        for &i in &category.rules {
+            let suffix = article_suffix(&pack.rules[i].article);
+            if suffix != main {
+                push(Severity::Warning, location(i, &pack.rules[i]), format!("сокращение '{}' не совпадает с остальными статьями категории ('{}')", suffix, main));
+            }
+        }
+    }
+    issues
+}
+
+/// Точка входа для --check-rules: печатает отчет и возвращает код выхода.
+pub fn run_check(path: &str, strict: bool) -> i32 {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) => { eprintln!("Не удалось прочитать {}: {}", path, e); return 1; }
+    };
+    let pack = match rules::parse_rules(&json) {
+        Ok(pack) => pack,
+        Err(e) => { println!("ОШИБКА   {}", e.describe().replacen(rules::RULES_FILE, path, 1)); return 1; }
+    };
+
+    let issues = lint_pack(&pack);
+    for issue in &issues {
+        let label = if issue.severity == Severity::Error { "ОШИБКА  " } else { "ВНИМАНИЕ" };
+        println!("{} {}: {}", label, issue.location, issue.message);
+    }
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+    println!("{}: {} статей, пакет {} - ошибок: {}, предупреждений: {}", path, pack.rules.len(), pack.title(), errors, warnings);
+    if errors > 0 || (strict && warnings > 0) { 1 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(category: &str, article: &str, description: &str, ban: &str) -> Rule {
+        Rule { description: description.into(), ban: ban.into(), ..Rule::for_test(category, article, "Статья") }
+    }
+
+    fn lint(rules: Vec<Rule>) -> Vec<(bool, String)> {
+        let pack = RulesPack { version: "1.0".into(), date: String::new(), rules };
+        lint_pack(&pack).into_iter().map(|i| (i.severity == Severity::Error, i.message)).collect()
+    }
+
+    #[test]
+    fn clean_pack_has_no_issues() {
+        assert!(lint(vec![rule("Правила", "1.1 ПП", "Строка`nстрока, см. 1.2 ПП", "3d/7d"), rule("Правила", "1.2 ПП", "", "")]).is_empty());
+    }
+
+    #[test]
+    fn bad_durations_and_broken_links() {
+        let issues = lint(vec![rule("Правила", "1.1 ПП", "см. 5.15 ПП", "3D")]);
+        assert!(issues.iter().any(|(error, m)| *error && m.contains("3D")));
+        assert!(issues.iter().any(|(error, m)| !*error && m.contains("5.15 ПП")));
+    }
+
+    #[test]
+    fn duplicates_and_category_spelling() {
+        let issues = lint(vec![rule("Правила", "1.1 ПП", "", ""), rule("Правила", "1.1 ПП", "", "")]);
+        assert_eq!(issues, [(false, "точный повтор статьи #1, можно удалить".to_string())]);
+        let issues = lint(vec![rule("Правила", "1.1 ПП", "", ""), rule("Правила", "1.1 ПП", "", "3d")]);
+        assert!(issues.iter().any(|(error, m)| *error && m.contains("номер уже занят")));
+        let issues = lint(vec![rule("Правила", "1.1 ПП", "", ""), rule("правила", "1.2 ПП", "", "")]);
+        assert!(issues.iter().any(|(error, m)| *error && m.contains("по-разному")));
+    }
+
+    #[test]
+    fn stray_escapes_and_suffixes() {
+        let issues = lint(vec![rule("Правила", "1.1 ПП", "текст`t", ""), rule("Правила", "1.2 ПП", "", ""), rule("Правила", "1.3 ПВ", "", "")]);
+        assert!(issues.iter().any(|(_, m)| m.contains("'`t'")));
+        assert!(issues.iter().any(|(_, m)| m.contains("'ПВ'")));
+    }
+
+    #[test]
+    fn shipped_pack_has_no_errors() {
+        // Предупреждения в нашем rules.json ожидаемы (ссылки и сокращения из оригинала), ошибок быть не должно
+        let pack = rules::parse_rules(include_str!("../rules.json")).expect("встроенный rules.json читается");
+        let errors: Vec<String> = lint_pack(&pack).into_iter().filter(|i| i.severity == Severity::Error).map(|i| format!("{}: {}", i.location, i.message)).collect();
+        assert!(errors.is_empty(), "{:#?}", errors);
+    }
+}
@@ -17,9 +17,6 @@ use chrono::{Local, Timelike, Datelike};
 use arboard::Clipboard; 
 use rdev::{listen, Event, EventType}; 
 use std::collections::HashMap;
-use reqwest; // Используем reqwest, как в твоем коде
-use open;
-use image; 
 
 #[cfg(target_os = "windows")]
 use std::mem::size_of;
@@ -45,6 +42,7 @@ use std::os::windows::ffi::OsStrExt;
 #[cfg(target_os = "windows")]
 extern "system" {
     fn SwitchToThisWindow(hwnd: winapi::shared::windef::HWND, fAltTab: winapi::shared::minwindef::BOOL);
+    fn AttachConsole(dwProcessId: winapi::shared::minwindef::DWORD) -> winapi::shared::minwindef::BOOL;
 }
 
 // Дубликаты импортов ureq/open/image убрал, оставил верхние
@@ -52,6 +50,23 @@ mod auth;
 use auth::AuthStatus;
 mod data;
 use data::{Organization, Teleport};
+mod punish;
+use punish::PunishmentOption;
+mod rules;
+use rules::{DescPart, RuleCategory, RuleChange, RuleRefs, RulesError, RulesPack};
+mod search;
+use search::{RuleIndex, SearchHit};
+mod journal;
+use journal::{JournalEntry, JournalFilter, JournalPeriod};
+mod batch;
+use batch::BatchQueue;
+mod reason;
+use reason::ReasonFields;
+mod lint;
+mod commands;
+mod history;
+mod hotstring;
+use hotstring::{BindOptions, HotstringContext, KeyBuffer, TriggerConflict};
 
 // ================= ЛОГИРОВАНИЕ =================
 static GLOBAL_LOGS: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
@@ -97,6 +112,7 @@ fn check_admin_rights() -> bool {
 }
 
 
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
 pub fn restore_application_window(ctx: &egui::Context) {
     #[cfg(target_os = "windows")]
     unsafe {
@@ -200,15 +216,35 @@ pub struct ActiveReplacement {
     pub trigger: String,
     pub label: String,
     pub text: String,
+    #[serde(default)]
+    pub options: BindOptions,
     #[serde(skip)]
     pub is_system: bool,
 }
 
+/// Шаблон ответа на жалобу (форум, Discord) - см. reason.rs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResponseTemplate {
+    pub name: String,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub admin_name: String,
     pub admin_id: String,
+    /// Уровень админки: 0 - Модер, 1-7 lvl. Недоступные команды скрываются или блокируются.
+    #[serde(default)] pub admin_level: u8,
+    /// Уровень выбран самим админом. Старые конфиги без уровня грузятся как Модер,
+    /// и программа один раз просит указать настоящий.
+    #[serde(default)] pub admin_level_set: bool,
+    /// Отправлять команды F6 сокращениями (/mc вместо /mutechat)
+    #[serde(default)] pub prefer_short_commands: bool,
+    /// Закрепленные команды целиком ("/dim 824151 3") - кнопки в истории F6
+    #[serde(default)] pub pinned_commands: Vec<String>,
     pub saved_triggers: HashMap<String, String>, 
+    /// Настройки стандартных биндов (по описанию), если отличаются от обычных
+    #[serde(default)] pub saved_bind_options: HashMap<String, BindOptions>,
     #[serde(default)] pub custom_replacements: Vec<ActiveReplacement>, 
     #[serde(default)] pub run_on_startup: bool,
     #[serde(default)] pub theme_mode: usize, 
@@ -218,20 +254,40 @@ pub struct AppConfig {
     #[serde(default = "default_key_event")] pub key_event: String,    
     #[serde(default = "default_key_mp")] pub key_mp: String,           
     #[serde(default = "default_key_reload")] pub key_reload: String, 
+    #[serde(default = "default_reason_template")] pub reason_template: String,
+    #[serde(default = "default_response_templates")] pub response_templates: Vec<ResponseTemplate>,
+    /// Номера статей ("3.2 ПП"), закрепленные вверху F7
+    #[serde(default)] pub favorite_rules: Vec<String>,
+    /// Последние выданные статьи, новые в начале
+    #[serde(default)] pub recent_rules: Vec<String>,
 }
 
+const RECENT_RULES_LIMIT: usize = 10;
+
 fn default_key_main() -> String { "NONE+F6".to_string() }
 fn default_key_punish() -> String { "NONE+F7".to_string() }
 fn default_key_event() -> String { "NONE+F8".to_string() }
 fn default_key_mp() -> String { "NONE+F10".to_string() }
 fn default_key_reload() -> String { "CONTROL+R".to_string() }
+fn default_reason_template() -> String { reason::DEFAULT_TEMPLATE.to_string() }
+fn default_response_templates() -> Vec<ResponseTemplate> {
+    vec![
+        ResponseTemplate { name: "Форум".to_string(), text: "Жалоба одобрена, игрок наказан по п. {article}[ на {duration}].[\nДоказательства: {evidence}]".to_string() },
+        ResponseTemplate { name: "Discord".to_string(), text: "**Жалоба[ {report}]: одобрена**\nИгрок: {id}\nНаказание: {punishment} ({article})[\nДоказательства: {evidence}]\nАдминистратор: {admin}".to_string() },
+    ]
+}
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             admin_name: "Administrator".to_string(),
             admin_id: String::new(),
+            admin_level: 0,
+            admin_level_set: false,
+            prefer_short_commands: false,
+            pinned_commands: Vec::new(),
             saved_triggers: HashMap::new(),
+            saved_bind_options: HashMap::new(),
             custom_replacements: Vec::new(),
             run_on_startup: false,
             theme_mode: 0, 
@@ -240,6 +296,10 @@ impl Default for AppConfig {
             key_event: default_key_event(),
             key_mp: default_key_mp(),
             key_reload: default_key_reload(),
+            reason_template: default_reason_template(),
+            response_templates: default_response_templates(),
+            favorite_rules: Vec::new(),
+            recent_rules: Vec::new(),
         }
     }
 }
@@ -260,11 +320,16 @@ pub struct Rule {
     #[serde(default)] pub ban_market: String,
 }
 
-#[derive(Clone, PartialEq)]
-struct PunishmentOption {
-    label: String,
-    cmd_base: String, 
-    time_arg: String, 
+#[cfg(test)]
+impl Rule {
+    /// Статья для тестов модулей: сроки и описание пустые, нужное задается через `..Rule::for_test(..)`.
+    pub fn for_test(category: &str, article: &str, title: &str) -> Self {
+        Rule {
+            category: category.into(), article: article.into(), title: title.into(), description: String::new(),
+            ban: String::new(), warn: 0, demorgan: String::new(), pacifist: String::new(), mutev: String::new(),
+            mute_chat: String::new(), mute_report: String::new(), ban_market: String::new(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -274,6 +339,16 @@ struct TimerState {
 }
 
 
+/// Релизная сборка без консоли: для --check-rules подключаемся к консоли, из которой запустили.
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS); }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
+
 #[cfg(target_os = "windows")]
 fn send_scan_code(scan_code: u16, press: bool) {
     unsafe {
@@ -293,6 +368,15 @@ fn send_scan_code(scan_code: u16, press: bool) {
 #[cfg(not(target_os = "windows"))]
 fn send_scan_code(_scan_code: u16, _press: bool) {}
 
+/// Окно, куда сейчас идет ввод; по смене окна слушатель биндов сбрасывает буфер.
+#[cfg(target_os = "windows")]
+fn foreground_window() -> usize {
+    unsafe { GetForegroundWindow() as usize }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn foreground_window() -> usize { 0 }
+
 fn type_in_game(ctx: Option<egui::Context>, text: String, open_chat: bool, press_enter: bool, finish_flag: Option<Arc<AtomicBool>>) {
     log(&format!("Action: Typing '{}'", text.replace("\n", " ")));
     
@@ -341,8 +425,14 @@ fn type_in_game(ctx: Option<egui::Context>, text: String, open_chat: bool, press
     });
 }
 
+/// Команда в чат с Enter + запись в историю команд.
+fn send_command(ctx: Option<egui::Context>, text: String, origin: &str) {
+    history::record(origin, &text);
+    type_in_game(ctx, text, true, true, None);
+}
+
 fn run_teleport(ctx: &egui::Context, coords: &str) {
-    type_in_game(Some(ctx.clone()), format!("/setpos {}", coords), true, true, None);
+    send_command(Some(ctx.clone()), format!("/setpos {}", coords), "Телепорты");
 }
 
 
@@ -431,11 +521,6 @@ fn input_to_bind_string(key: egui::Key, modifiers: egui::Modifiers) -> String {
     parts.join("+")
 }
 
-fn load_rules() -> Vec<Rule> {
-    let rules_json = include_str!("../rules.json"); 
-    serde_json::from_str(rules_json).unwrap_or_default()
-}
-
 fn load_config() -> AppConfig {
     match fs::read_to_string("config.json") {
         Ok(json) => serde_json::from_str(&json).unwrap_or_default(),
@@ -509,15 +594,14 @@ fn apply_theme(ctx: &egui::Context, theme_index: usize) {
     ctx.set_visuals(visuals);
 }
 
-fn start_hotstring_listener(shared_replacements: Arc<Mutex<Vec<ActiveReplacement>>>) {
-    let (tx, rx) = mpsc::channel::<ActiveReplacement>();
+fn start_hotstring_listener(shared_replacements: Arc<Mutex<Vec<ActiveReplacement>>>, hotstring_context: Arc<Mutex<HotstringContext>>, prompt_sender: mpsc::Sender<hotstring::PromptRequest>, ctx: egui::Context) {
+    let (tx, rx) = mpsc::channel::<(ActiveReplacement, usize)>();
 
     thread::spawn(move || {
-        while let Ok(rep) = rx.recv() {
+        while let Ok((rep, erase)) = rx.recv() {
             log(&format!("Hotstring triggered: {}", rep.label));
             thread::sleep(Duration::from_millis(50));
-            let len = rep.trigger.chars().count();
-            for _ in 0..len {
+            for _ in 0..erase {
                 #[cfg(target_os = "windows")]
                 {
                     send_scan_code(0x0E, true); 
@@ -526,35 +610,45 @@ fn start_hotstring_listener(shared_replacements: Arc<Mutex<Vec<ActiveReplacement
                     thread::sleep(Duration::from_millis(10));
                 }
             }
-            type_in_game(None, rep.text, false, false, None);
+            let context = hotstring_context.lock().map(|c| c.clone()).unwrap_or_default();
+            let text = hotstring::expand(&rep.text, &context);
+            let fields = hotstring::prompt_fields(&text);
+            if fields.is_empty() {
+                type_in_game(None, text, false, false, None);
+            } else {
+                // Поля {?..} заполняются в окне поверх игры, текст уйдет после "Отправить".
+                // Свернутое главное окно не перерисовывается, поэтому разворачиваем его как по хоткею.
+                let _ = prompt_sender.send(hotstring::PromptRequest { label: rep.label.clone(), text, fields });
+                restore_application_window(&ctx);
+                ctx.request_repaint();
+            }
         }
     });
 
     thread::spawn(move || {
-        let mut buffer = String::new();
+        let mut buffer = KeyBuffer::default();
+        let mut window = foreground_window();
         let callback = move |event: Event| {
-            if let EventType::KeyPress(key) = event.event_type {
-                if key == rdev::Key::Return || key == rdev::Key::Escape {
-                    buffer.clear();
-                } 
-                else if let Some(name) = event.name {
-                    if name == "\u{8}" { 
-                        buffer.pop();
-                    } else {
-                        buffer.push_str(&name);
-                        if buffer.len() > 30 { buffer.remove(0); }
-                    }
-
+            match event.event_type {
+                EventType::KeyPress(key) => {
+                    // Alt+Tab, Win+L и т.п.: отпускание клавиш в старом окне слушатель может не увидеть
+                    let now = foreground_window();
+                    if now != window { buffer.clear(); window = now; }
+                    buffer.press(key, event.name.as_deref());
                     if let Ok(replacements) = shared_replacements.lock() {
-                        for rep in replacements.iter() {
-                            if !rep.trigger.is_empty() && buffer.to_lowercase().ends_with(&rep.trigger.to_lowercase()) {
-                                buffer.clear();
-                                let _ = tx.send(rep.clone());
-                                break; 
-                            }
+                        // Если на одном нажатии подошло несколько биндов, срабатывает самый короткий -
+                        // так же считает конфликты hotstring::find_conflicts
+                        let hit = replacements.iter()
+                            .filter_map(|rep| buffer.check(&rep.trigger, &rep.options).map(|erase| (rep, erase)))
+                            .min_by_key(|(rep, _)| rep.trigger.chars().count());
+                        if let Some((rep, erase)) = hit {
+                            buffer.clear();
+                            let _ = tx.send((rep.clone(), erase));
                         }
                     }
                 }
+                EventType::KeyRelease(key) => buffer.release(key),
+                _ => {}
             }
         };
         if let Err(error) = listen(callback) {
@@ -563,12 +657,39 @@ fn start_hotstring_listener(shared_replacements: Arc<Mutex<Vec<ActiveReplacement
     });
 }
 
+fn bind_conflicts(replacements: &[ActiveReplacement]) -> Vec<TriggerConflict> {
+    let binds: Vec<(&str, &BindOptions)> = replacements.iter().map(|r| (r.trigger.as_str(), &r.options)).collect();
+    hotstring::find_conflicts(&binds)
+}
+
+/// Подсказки к биндам с конфликтами: индекс в списке → что с ним не так.
+fn conflict_notes(replacements: &[ActiveReplacement], conflicts: &[TriggerConflict]) -> HashMap<usize, Vec<String>> {
+    let mut notes: HashMap<usize, Vec<String>> = HashMap::new();
+    for c in conflicts {
+        let (shadowed, by) = (&replacements[c.shadowed], &replacements[c.by]);
+        notes.entry(c.shadowed).or_default().push(format!("Не сработает: «{}» ({}) {}", by.trigger, by.label, c.kind.describe()));
+        notes.entry(c.by).or_default().push(format!("Из-за этого бинда не сработает «{}» ({})", shadowed.trigger, shadowed.label));
+    }
+    notes
+}
+
 // ================= GUI =================
 
+/// Меню "⚙" у бинда: как он срабатывает.
+fn bind_options_menu(ui: &mut egui::Ui, options: &mut BindOptions) {
+    ui.menu_button("⚙", |ui| {
+        ui.checkbox(&mut options.any_layout, "Любая раскладка").on_hover_text("Срабатывает на те же клавиши в русской и английской раскладке: «п1» = «g1»");
+        ui.checkbox(&mut options.word_start, "Только с начала слова").on_hover_text("Перед биндом должен быть пробел, знак препинания или начало строки: «ку1» не сработает внутри «паку1»");
+        ui.checkbox(&mut options.end_key, "Подтверждать пробелом или Tab").on_hover_text("Бинд срабатывает только после пробела или Tab за ним");
+        ui.checkbox(&mut options.case_sensitive, "Учитывать регистр").on_hover_text("«П1» и «п1» - разные бинды");
+        ui.checkbox(&mut options.keep_trigger, "Не стирать бинд").on_hover_text("Набранный бинд остается, текст дописывается после него");
+    }).response.on_hover_text("Настройки срабатывания");
+}
+
 #[derive(PartialEq)]
-enum F6Tab { Description, Commands, AutoReplace, Events, OrgManager, OnlineTimer, BugReport }
+enum F6Tab { Description, Commands, History, AutoReplace, Events, OrgManager, OnlineTimer, BugReport }
 #[derive(PartialEq)]
-enum MainTab { Setup, InfoF6, PunishF7, TeleportF8, MpF9, Logs }
+enum MainTab { Setup, InfoF6, PunishF7, TeleportF8, MpF9, Journal, Logs }
 #[derive(PartialEq)]
 enum F9Tab { Commands, Teleports }
 
@@ -628,8 +749,15 @@ struct MyApp {
     // === СТАРЫЕ ПОЛЯ ===
     config: AppConfig,
     rules: Vec<Rule>,
+    rule_index: RuleIndex,
+    rule_tree: Vec<RuleCategory>,
+    rule_refs: RuleRefs,
+    search_cache: (String, Vec<SearchHit>),
     orgs: Vec<Organization>,
     active_replacements: Arc<Mutex<Vec<ActiveReplacement>>>,
+    hotstring_context: Arc<Mutex<HotstringContext>>,
+    prompt_receiver: std::sync::mpsc::Receiver<hotstring::PromptRequest>,
+    pending_prompt: Option<(hotstring::PromptRequest, Vec<String>)>,
     current_tab: MainTab,
     f6_tab: F6Tab,
     f9_tab: F9Tab,
@@ -637,7 +765,15 @@ struct MyApp {
     selected_org_index: usize,
     selected_rank_index: usize,
     cmd_search: String,
+    show_locked_commands: bool,
+    /// Открытая форма F6: имя команды и значения аргументов
+    cmd_form: Option<(&'static str, Vec<String>)>,
+    history_search: String,
+    /// Команда из истории, открытая для правки перед отправкой
+    history_edit: String,
     replace_search: String,
+    /// Почему бинды не сохранились (конфликт триггеров)
+    bind_save_error: Option<String>,
     new_rep_trigger: String,
     new_rep_label: String,
     new_rep_text: String,
@@ -646,12 +782,47 @@ struct MyApp {
     timer_paused: bool,
     last_reset_day: u32,
     search_text: String,
+    /// Строка под курсором клавиатуры в списке F7
+    rule_cursor: usize,
+    /// Раскрытые категории дерева F7 (с прошлого кадра): стрелки ходят и по ним
+    open_rule_categories: Vec<String>,
+    focus_search: bool,
+    focus_punish_id: bool,
+    only_punishable: bool,
     input_id: String,
     input_report_num: String,
+    input_evidence: String,
+    selected_response: usize,
     input_violation_time: String,
+    input_prior_count: u32,
+    input_custom_duration: String,
     selected_rule: Option<Rule>, 
     selected_punishment_idx: usize,
     generated_punish_cmd: String, 
+    punish_explanation: String,
+    punish_warning: String,
+    punish_long_bans: &'static [&'static str],
+    selected_long_ban: String,
+    punish_cmd_valid: bool,
+    // Команды F7 по каждому ID из поля + записи журнала для них
+    punish_batch: Vec<(String, JournalEntry)>,
+    batch_queue: BatchQueue,
+    // Несколько нарушений сразу: статья + выбранный вариант наказания
+    combo_rules: Vec<punish::ComboPart>,
+    journal: Vec<JournalEntry>,
+    journal_filter: JournalFilter,
+    // Наказание, которое снимаем, и номер обжалования (пусто - ошибка выдачи)
+    revoke_target: Option<JournalEntry>,
+    revoke_appeal: String,
+    rule_errors: Vec<String>,
+    rules_load_error: Option<RulesError>,
+    /// rules.json заменен встроенной редакцией поновее
+    rules_notice: Option<String>,
+    rules_receiver: std::sync::mpsc::Receiver<Result<RulesPack, RulesError>>,
+    rules_title: String,
+    rules_prev_title: Option<String>,
+    rules_diff: Vec<RuleChange>,
+    show_rules_diff: bool,
     teleport_list: Vec<Teleport>,
     teleport_search: String,
     teleport_category: String,
@@ -680,8 +851,9 @@ impl MyApp {
         let mut combined_replacements = Vec::new();
         for (label, text) in raw_data {
             let trigger = config.saved_triggers.get(label).cloned().unwrap_or_default();
+            let options = config.saved_bind_options.get(label).cloned().unwrap_or_default();
             combined_replacements.push(ActiveReplacement {
-                trigger, label: label.to_string(), text: text.to_string(), is_system: true,
+                trigger, label: label.to_string(), text: text.to_string(), options, is_system: true,
             });
         }
         for mut custom in config.custom_replacements.clone() {
@@ -689,7 +861,9 @@ impl MyApp {
             combined_replacements.push(custom);
         }
         let shared_replacements = Arc::new(Mutex::new(combined_replacements));
-        start_hotstring_listener(shared_replacements.clone());
+        let hotstring_context = Arc::new(Mutex::new(HotstringContext { admin_name: config.admin_name.clone(), admin_id: config.admin_id.clone(), ..Default::default() }));
+        let (tx_prompt, rx_prompt) = mpsc::channel::<hotstring::PromptRequest>();
+        start_hotstring_listener(shared_replacements.clone(), hotstring_context.clone(), tx_prompt, cc.egui_ctx.clone());
 
         let (tx_config, rx_config) = mpsc::channel::<AppConfig>();
         let (tx_action, rx_action) = mpsc::channel::<HotkeyAction>();
@@ -784,15 +958,33 @@ impl MyApp {
 
         let start_tab = if config.admin_name.is_empty() { MainTab::Setup } else { MainTab::InfoF6 };
 
+        let (pack, rules_load_error, rules_notice) = rules::load_rules();
+        let (tx_rules, rx_rules) = mpsc::channel();
+        rules::start_rules_watcher(tx_rules);
+        // Встроенную копию при битом файле не считаем установленной редакцией
+        let previous_pack = if rules_load_error.is_none() { rules::install_pack(&pack) } else { None };
+        let rules_diff = previous_pack.as_ref().map(|p| rules::diff_rules(&p.rules, &pack.rules)).unwrap_or_default();
+        let rules_title = pack.title();
+        let rules = pack.rules;
+        let rule_errors = punish::validate_rules(&rules);
+        for e in &rule_errors { log(&format!("Rules: {}", e)); }
+
         Self {
             state: AppState::Login, // <-- НАЧИНАЕМ С ЭКРАНА ВХОДА
             login_user: String::new(),
             login_pass: String::new(),
             login_status: String::new(),
             config,
-            rules: load_rules(),
+            rule_index: RuleIndex::build(&rules),
+            rule_tree: rules::category_tree(&rules),
+            rule_refs: RuleRefs::build(&rules),
+            search_cache: (String::new(), Vec::new()),
+            rules,
             orgs: data::get_organizations(),
             active_replacements: shared_replacements,
+            hotstring_context,
+            prompt_receiver: rx_prompt,
+            pending_prompt: None,
             current_tab: start_tab,
             f6_tab: F6Tab::Description,
             f9_tab: F9Tab::Commands,
@@ -800,7 +992,12 @@ impl MyApp {
             selected_org_index: 0,
             selected_rank_index: 0,
             cmd_search: String::new(),
+            show_locked_commands: false,
+            cmd_form: None,
+            history_search: String::new(),
+            history_edit: String::new(),
             replace_search: String::new(),
+            bind_save_error: None,
             new_rep_trigger: String::new(),
             new_rep_label: String::new(),
             new_rep_text: String::new(),
@@ -809,12 +1006,41 @@ impl MyApp {
             timer_paused: false,
             last_reset_day: last_day,
             search_text: String::new(),
+            rule_cursor: 0,
+            open_rule_categories: Vec::new(),
+            focus_search: false,
+            focus_punish_id: false,
+            only_punishable: false,
             input_id: String::new(),
             input_report_num: String::new(),
+            input_evidence: String::new(),
+            selected_response: 0,
             input_violation_time: String::new(),
+            input_prior_count: 0,
+            input_custom_duration: String::new(),
             selected_rule: None,
             selected_punishment_idx: 0,
             generated_punish_cmd: String::new(),
+            punish_explanation: String::new(),
+            punish_warning: String::new(),
+            punish_long_bans: &[],
+            selected_long_ban: String::new(),
+            punish_cmd_valid: false,
+            punish_batch: Vec::new(),
+            batch_queue: BatchQueue::default(),
+            combo_rules: Vec::new(),
+            journal: journal::load_journal(),
+            journal_filter: JournalFilter::default(),
+            revoke_target: None,
+            revoke_appeal: String::new(),
+            rule_errors,
+            rules_load_error,
+            rules_notice,
+            rules_receiver: rx_rules,
+            rules_title,
+            rules_prev_title: previous_pack.as_ref().map(|p| p.title()),
+            rules_diff,
+            show_rules_diff: false,
             teleport_list: data::get_teleports(),
             teleport_search: String::new(),
             teleport_category: "Все события".to_string(),
@@ -828,6 +1054,36 @@ impl MyApp {
         }
     }
 
+    fn apply_rules_update(&mut self, update: Result<RulesPack, RulesError>) {
+        match update {
+            Ok(pack) => {
+                self.rule_errors = punish::validate_rules(&pack.rules);
+                for e in &self.rule_errors { log(&format!("Rules: {}", e)); }
+                log(&format!("Rules: loaded {} rules ({})", pack.rules.len(), pack.title()));
+                let previous = rules::install_pack(&pack);
+                self.rules_diff = previous.as_ref().map(|p| rules::diff_rules(&p.rules, &pack.rules)).unwrap_or_default();
+                self.rules_prev_title = previous.map(|p| p.title());
+                self.rules_title = pack.title();
+                self.rule_index = RuleIndex::build(&pack.rules);
+                self.rule_tree = rules::category_tree(&pack.rules);
+                self.rule_refs = RuleRefs::build(&pack.rules);
+                self.search_cache = (String::new(), Vec::new());
+                self.rules = pack.rules;
+                self.rules_load_error = None;
+            }
+            // Битый файл не сбрасывает уже загруженные правила
+            Err(e) => self.rules_load_error = Some(e),
+        }
+    }
+
+    /// Результаты поиска F7; индекс пересчитывается только при смене запроса.
+    fn search_rules(&mut self) -> Vec<SearchHit> {
+        if self.search_cache.0 != self.search_text {
+            self.search_cache = (self.search_text.clone(), self.rule_index.search(&self.search_text));
+        }
+        self.search_cache.1.clone()
+    }
+
     fn update_hotkeys(&mut self) {
         let _ = self.hotkey_sender.send(self.config.clone());
     }
@@ -850,12 +1106,27 @@ impl MyApp {
         if let Ok(json) = serde_json::to_string(&state) { let _ = fs::write("timer.json", json); }
     }
     fn save_triggers(&mut self) {
+        if let Ok(replacements) = self.active_replacements.lock() {
+            let conflicts = bind_conflicts(&replacements);
+            if !conflicts.is_empty() {
+                log(&format!("Binds: not saved, {} trigger conflicts", conflicts.len()));
+                self.bind_save_error = Some(format!("Не сохранено: конфликтов между биндами - {}. Исправьте бинды, отмеченные ⚠ (при закрытии программы они сохранятся как есть)", conflicts.len()));
+                return;
+            }
+            self.bind_save_error = None;
+        }
+        self.store_triggers();
+    }
+    /// Запись биндов в конфиг без проверки конфликтов.
+    fn store_triggers(&mut self) {
         if let Ok(replacements) = self.active_replacements.lock() {
             self.config.saved_triggers.clear();
+            self.config.saved_bind_options.clear();
             self.config.custom_replacements.clear();
             for rep in replacements.iter() {
                 if rep.is_system {
                     if !rep.trigger.is_empty() { self.config.saved_triggers.insert(rep.label.clone(), rep.trigger.clone()); }
+                    if rep.options != BindOptions::default() { self.config.saved_bind_options.insert(rep.label.clone(), rep.options.clone()); }
                 } else { self.config.custom_replacements.push(rep.clone()); }
             }
         }
@@ -869,39 +1140,223 @@ impl MyApp {
     }
     fn check_daily_reset(&mut self) {
         let now = Local::now();
-        if now.day() != self.last_reset_day {
-            if now.hour() >= 3 {
-                self.timer_saved_seconds = 0; self.timer_start = Instant::now(); self.last_reset_day = now.day(); self.save_timer();
-            }
+        if now.day() != self.last_reset_day && now.hour() >= 3 {
+            self.timer_saved_seconds = 0; self.timer_start = Instant::now(); self.last_reset_day = now.day(); self.save_timer();
         }
     }
     fn reset_timer(&mut self) { self.timer_saved_seconds = 0; self.timer_start = Instant::now(); self.save_timer(); }
     fn get_rule_options(rule: &Rule) -> Vec<PunishmentOption> {
-        let mut options = Vec::new();
-        if !rule.demorgan.is_empty() { for part in rule.demorgan.split('/') { options.push(PunishmentOption { label: format!("Demorgan {}", part), cmd_base: "/ban".to_string(), time_arg: part.trim().to_string() }); } }
-        if !rule.ban.is_empty() { for part in rule.ban.split('/') { options.push(PunishmentOption { label: format!("Ban {}", part), cmd_base: "/ban".to_string(), time_arg: part.trim().to_string() }); } }
-        if rule.warn > 0 { options.push(PunishmentOption { label: "Warn".to_string(), cmd_base: "/warn".to_string(), time_arg: "".to_string() }); }
-        if !rule.mutev.is_empty() { options.push(PunishmentOption { label: format!("Voice Mute {}", rule.mutev), cmd_base: "/mutevoice".to_string(), time_arg: rule.mutev.clone() }); }
-        if !rule.mute_chat.is_empty() { options.push(PunishmentOption { label: format!("Chat Mute {}", rule.mute_chat), cmd_base: "/mutechat".to_string(), time_arg: rule.mute_chat.clone() }); }
-        if !rule.mute_report.is_empty() { options.push(PunishmentOption { label: format!("Report Mute {}", rule.mute_report), cmd_base: "/mutereport".to_string(), time_arg: rule.mute_report.clone() }); }
-        if !rule.pacifist.is_empty() { options.push(PunishmentOption { label: format!("Pacifist {}", rule.pacifist), cmd_base: "/pacifist".to_string(), time_arg: rule.pacifist.clone() }); }
-        if !rule.ban_market.is_empty() { options.push(PunishmentOption { label: format!("Ban Market {}", rule.ban_market), cmd_base: "/ban_market_content_creation".to_string(), time_arg: rule.ban_market.clone() }); }
-        options
+        punish::rule_options(rule)
+    }
+    fn report_number(&self) -> String {
+        if self.input_report_num.is_empty() || self.input_report_num.contains("№") { self.input_report_num.clone() } else { format!("№{}", self.input_report_num) }
+    }
+    /// Причина по шаблону из настроек: "3.2 ПП + 5.1 ПП (Ранее 12:00) | №1234"
+    fn punish_reason(&self, articles: &str, titles: &str) -> String {
+        reason::render(&self.config.reason_template, &self.reason_fields(articles, titles))
+    }
+    fn reason_fields(&self, articles: &str, titles: &str) -> ReasonFields {
+        ReasonFields {
+            article: articles.to_string(),
+            title: titles.to_string(),
+            time: self.input_violation_time.trim().to_string(),
+            report: self.report_number(),
+            admin: self.config.admin_name.clone(),
+            date: Local::now().format("%d.%m.%Y").to_string(),
+            evidence: reason::evidence_links(&self.input_evidence).unwrap_or_default().join(" "),
+            ..Default::default()
+        }
+    }
+    /// Ответ на жалобу по выбранному шаблону для уже составленных команд.
+    fn response_text(&self, commands: &[(String, JournalEntry)], titles: &str) -> String {
+        let Some(template) = self.config.response_templates.get(self.selected_response) else { return String::new() };
+        let unique = |values: Vec<String>| values.into_iter().fold(Vec::<String>::new(), |mut acc, v| { if !v.is_empty() && !acc.contains(&v) { acc.push(v); } acc });
+        let articles = unique(commands.iter().flat_map(|(_, e)| e.article.split(" + ").map(|a| a.to_string()).collect::<Vec<_>>()).collect());
+        let mut fields = self.reason_fields(&articles.join(", "), titles);
+        fields.evidence = reason::evidence_links(&self.input_evidence).unwrap_or_default().join("\n");
+        fields.id = unique(commands.iter().map(|(_, e)| e.player_id.clone()).collect()).join(", ");
+        fields.punishment = unique(commands.iter().map(|(_, e)| e.punishment_text()).collect()).join(", ");
+        fields.duration = unique(commands.iter().map(|(_, e)| e.duration.clone()).collect()).join(", ");
+        fields.command = commands.iter().map(|(c, _)| c.as_str()).collect::<Vec<_>>().join("\n");
+        reason::render_response(&template.text, &fields)
+    }
+    /// Команды для всех статей из комбинации + записи для журнала.
+    fn combo_commands(&self) -> (Vec<(String, JournalEntry)>, punish::Combination) {
+        let mut combination = punish::combine(&self.combo_rules);
+        let ids = batch::parse_id_list(&self.input_id).unwrap_or_else(|e| { combination.errors.push(e); Vec::new() });
+        let mut commands = Vec::new();
+        // Пустой список ID - предпросмотр с "ID", кнопка «Выдать все» при этом выключена
+        for id in if ids.is_empty() { vec!["ID".to_string()] } else { ids } {
+            for c in &combination.commands {
+                let articles = c.articles.join(" + ");
+                let titles = self.combo_rules.iter().filter(|p| c.articles.contains(&p.rule.article)).map(|p| p.rule.title.as_str()).collect::<Vec<_>>().join(" + ");
+                let reason = self.punish_reason(&articles, &titles);
+                let cmd = if c.time_arg.is_empty() { format!("{} {} {}", c.cmd_base, id, reason) } else { format!("{} {} {} {}", c.cmd_base, id, c.time_arg, reason) };
+                let duration = if c.cmd_base == "/perma" { "навсегда".to_string() } else { c.time_arg.clone() };
+                let category = self.combo_rules.iter().find(|p| c.articles.contains(&p.rule.article)).map(|p| p.rule.category.clone()).unwrap_or_default();
+                let entry = JournalEntry { player_id: id.clone(), article: articles, category, punishment: c.kind.label().to_string(), duration, report: self.report_number(), admin: self.config.admin_name.clone(), command: cmd.clone(), ..Default::default() };
+                commands.push((cmd, entry));
+            }
+        }
+        (commands, combination)
+    }
+    fn response_picker(&mut self, ui: &mut egui::Ui) {
+        if self.selected_response >= self.config.response_templates.len() { self.selected_response = 0; }
+        let current = self.config.response_templates.get(self.selected_response).map(|t| t.name.clone()).unwrap_or_default();
+        egui::ComboBox::from_id_source("response_template").selected_text(current).show_ui(ui, |ui| {
+            for (i, t) in self.config.response_templates.iter().enumerate() { ui.selectable_value(&mut self.selected_response, i, &t.name); }
+        });
+    }
+    /// Избранное, затем недавние (без повторов) - индексы в списке правил.
+    fn quick_rules(&self) -> Vec<usize> {
+        let mut quick: Vec<usize> = Vec::new();
+        for article in self.config.favorite_rules.iter().chain(&self.config.recent_rules) {
+            if let Some(i) = self.rules.iter().position(|r| &r.article == article) {
+                if !quick.contains(&i) { quick.push(i); }
+            }
+        }
+        quick
+    }
+
+    fn toggle_favorite(&mut self, article: &str) {
+        match self.config.favorite_rules.iter().position(|a| a == article) {
+            Some(i) => { self.config.favorite_rules.remove(i); }
+            None => self.config.favorite_rules.push(article.to_string()),
+        }
+        save_config(&self.config);
+    }
+
+    fn remember_recent(&mut self, articles: &[&str]) {
+        for article in articles.iter().rev() {
+            self.config.recent_rules.retain(|a| a != article);
+            self.config.recent_rules.insert(0, article.to_string());
+        }
+        self.config.recent_rules.truncate(RECENT_RULES_LIMIT);
+        save_config(&self.config);
+    }
+
+    /// Кнопка «Выдать» или Enter в поле ID.
+    fn issue_punishment(&mut self, ctx: &egui::Context) {
+        if !self.punish_cmd_valid || !reason::too_long_lines(&self.generated_punish_cmd).is_empty() || self.batch_queue.is_running() { return; }
+        if data::check_level(&self.generated_punish_cmd, self.config.admin_level).is_err() { return; }
+        // Команды могли поправить руками в поле команды
+        let lines: Vec<&str> = self.generated_punish_cmd.lines().filter(|l| !l.trim().is_empty()).collect();
+        let mut commands = self.punish_batch.clone();
+        if lines.len() == commands.len() {
+            for ((cmd, entry), line) in commands.iter_mut().zip(lines) { *cmd = line.trim().to_string(); entry.command = cmd.clone(); }
+        }
+        if commands.len() > 1 {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            self.batch_queue.start(commands);
+        } else if let Some((cmd, mut entry)) = commands.pop() {
+            send_command(Some(ctx.clone()), cmd, "F7");
+            entry.stamp_now();
+            journal::append_entry(&entry);
+            self.journal.push(entry);
+        }
+        if let Some(rule) = self.selected_rule.clone() { self.remember_recent(&[&rule.article]); }
+    }
+
+    fn select_rule(&mut self, rule: Rule) {
+        self.selected_rule = Some(rule); self.selected_punishment_idx = 0; self.input_prior_count = 0; self.input_custom_duration.clear(); self.selected_long_ban.clear(); self.update_punish_command();
     }
+
+    /// Данные для подстановок в биндах ({admin_name}, {last_id}...).
+    fn sync_hotstring_context(&self) {
+        let Ok(mut context) = self.hotstring_context.lock() else { return };
+        context.admin_name = self.config.admin_name.clone();
+        context.admin_id = self.config.admin_id.clone();
+        // {last_id} и {last_article} помнят последнее введенное, даже если поле потом очистили
+        let id = batch::parse_id_list(&self.input_id).ok().and_then(|ids| ids.into_iter().next());
+        if let Some(id) = id { context.last_id = id; }
+        if let Some(rule) = &self.selected_rule { context.last_article = rule.article.clone(); }
+    }
+
+    /// Окно поверх игры для полей {?ID} сработавшего бинда.
+    fn show_bind_prompt(&mut self, ctx: &egui::Context) {
+        if self.pending_prompt.is_none() {
+            if let Ok(request) = self.prompt_receiver.try_recv() {
+                let values = vec![String::new(); request.fields.len()];
+                self.pending_prompt = Some((request, values));
+            }
+        }
+        let Some((request, values)) = &mut self.pending_prompt else { return };
+        let height = 70.0 + request.fields.len() as f32 * 28.0;
+        let viewport = egui::ViewportBuilder::default()
+            .with_title(format!("Бинд: {}", request.label))
+            .with_inner_size([320.0, height])
+            .with_resizable(false)
+            .with_always_on_top()
+            .with_active(true);
+        let mut send = false;
+        let mut cancel = false;
+        ctx.show_viewport_immediate(egui::ViewportId::from_hash_of("bind_prompt"), viewport, |ctx, _| {
+            if ctx.input(|i| i.viewport().close_requested()) { cancel = true; }
+            egui::CentralPanel::default().show(ctx, |ui| {
+                egui::Grid::new("bind_prompt_grid").num_columns(2).spacing([8.0, 6.0]).show(ui, |ui| {
+                    for (i, (name, value)) in request.fields.iter().zip(values.iter_mut()).enumerate() {
+                        ui.label(format!("{}:", name));
+                        let response = ui.add(egui::TextEdit::singleline(value).desired_width(200.0));
+                        if i == 0 && ui.memory(|m| m.focused().is_none()) { response.request_focus(); }
+                        ui.end_row();
+                    }
+                });
+                let ready = values.iter().all(|v| !v.trim().is_empty());
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(ready, egui::Button::new("Отправить")).clicked() { send = true; }
+                    if ui.button("Отмена").clicked() { cancel = true; }
+                });
+                if ready && ui.input(|i| i.key_pressed(egui::Key::Enter)) { send = true; }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) { cancel = true; }
+            });
+        });
+        if send {
+            let text = hotstring::fill_prompts(&request.text, &request.fields, values);
+            log(&format!("Hotstring prompt filled: {}", request.label));
+            self.pending_prompt = None;
+            // Главное окно сворачивается, фокус возвращается игре внутри type_in_game
+            type_in_game(Some(ctx.clone()), text, false, false, None);
+        } else if cancel {
+            log(&format!("Hotstring prompt cancelled: {}", request.label));
+            self.pending_prompt = None;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            thread::spawn(focus_game_window);
+        }
+    }
+
     fn update_punish_command(&mut self) {
+        self.sync_hotstring_context();
         if let Some(rule) = &self.selected_rule {
             let options = Self::get_rule_options(rule);
-            if options.is_empty() { self.generated_punish_cmd = "На данный пункт правила не предусмотрены наказание.".to_string(); return; }
+            self.punish_cmd_valid = false;
+            self.punish_batch.clear();
+            self.punish_warning.clear();
+            self.punish_long_bans = &[];
+            if options.is_empty() { self.generated_punish_cmd = "На данный пункт правила не предусмотрены наказание.".to_string(); self.punish_explanation.clear(); return; }
             if self.selected_punishment_idx >= options.len() { self.selected_punishment_idx = 0; }
             let action = &options[self.selected_punishment_idx];
-            let mut reason = rule.article.clone();
-            if !self.input_violation_time.is_empty() { reason = format!("{} (Ранее {})", reason, self.input_violation_time); }
-            if !self.input_report_num.is_empty() {
-                let report_str = if self.input_report_num.contains("№") { self.input_report_num.clone() } else { format!("№{}", self.input_report_num) };
-                reason = format!("{} | {}", reason, report_str);
+            let choice = action.choose(self.input_prior_count, &self.input_custom_duration, &self.selected_long_ban);
+            self.punish_explanation = choice.explanation;
+            self.punish_warning = choice.warning.unwrap_or_default();
+            self.punish_long_bans = choice.alternatives;
+            if self.punish_long_bans.contains(&choice.cmd_base.as_str()) { self.selected_long_ban = choice.cmd_base.clone(); }
+            if let Some(err) = choice.error { self.generated_punish_cmd = format!("⚠ {}", err); return; }
+            let ids = match batch::parse_id_list(&self.input_id) { Ok(ids) => ids, Err(e) => { self.generated_punish_cmd = format!("⚠ {}", e); return; } };
+            let reason = self.punish_reason(&rule.article, &rule.title);
+            let report_str = self.report_number();
+            let duration = if choice.cmd_base == "/perma" { "навсегда".to_string() } else { choice.time_arg.clone() };
+            let command_for = |id: &str| if choice.time_arg.is_empty() { format!("{} {} {}", choice.cmd_base, id, reason) } else { format!("{} {} {} {}", choice.cmd_base, id, choice.time_arg, reason) };
+            // Без ID команда только для предпросмотра: "ID" не отправляется и не попадает в журнал
+            if ids.is_empty() { self.generated_punish_cmd = command_for("ID"); return; }
+            for id in ids {
+                let cmd = command_for(&id);
+                let entry = JournalEntry { player_id: id, article: rule.article.clone(), category: rule.category.clone(), punishment: action.kind.label().to_string(), duration: duration.clone(), report: report_str.clone(), admin: self.config.admin_name.clone(), command: cmd.clone(), ..Default::default() };
+                self.punish_batch.push((cmd, entry));
             }
-            let id = if self.input_id.is_empty() { "ID" } else { &self.input_id };
-            self.generated_punish_cmd = if action.time_arg.is_empty() { format!("{} {} {}", action.cmd_base, id, reason) } else { format!("{} {} {} {}", action.cmd_base, id, action.time_arg, reason) };
+            // Для нескольких ID поле показывает все команды (предпросмотр очереди)
+            self.generated_punish_cmd = self.punish_batch.iter().map(|(c, _)| c.as_str()).collect::<Vec<_>>().join("\n");
+            self.punish_cmd_valid = true;
         }
     }
 }
@@ -909,7 +1364,13 @@ impl MyApp {
 impl eframe::App for MyApp {
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         self.save_timer();
-        self.save_triggers();
+        // При закрытии правки не теряем: бинды сохраняются и с конфликтами, а сами конфликты пишем в лог
+        if let Ok(replacements) = self.active_replacements.lock() {
+            for notes in conflict_notes(&replacements, &bind_conflicts(&replacements)).values() {
+                for note in notes { log(&format!("Binds: saved with conflict - {}", note)); }
+            }
+        }
+        self.store_triggers();
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -958,10 +1419,12 @@ impl eframe::App for MyApp {
                             let status = auth::try_login(&self.login_user, &self.login_pass);
                             
                             match status {
-                                AuthStatus::Success(_) => {
+                                AuthStatus::Success(name) => {
+                                    log(&format!("Auth: logged in as {}", name));
                                     self.state = AppState::Main; // ПУСКАЕМ В ПРОГРАММУ
                                     self.config.admin_name = self.login_user.clone();
                                     save_config(&self.config);
+                                    self.sync_hotstring_context();
                                 },
                                 AuthStatus::WrongCredentials => self.login_status = "❌ Неверный логин или пароль".to_string(),
                                 AuthStatus::HwidMismatch => self.login_status = "⛔ HWID не совпадает! (Чужой ПК)".to_string(),
@@ -986,7 +1449,7 @@ impl eframe::App for MyApp {
                     log(&format!("[UI] Switching tab to: {:?}", action));
                     match action {
                         HotkeyAction::MainMenu => self.current_tab = MainTab::InfoF6,
-                        HotkeyAction::PunishMenu => self.current_tab = MainTab::PunishF7,
+                        HotkeyAction::PunishMenu => { self.current_tab = MainTab::PunishF7; self.focus_search = true; }
                         HotkeyAction::EventsMenu => self.current_tab = MainTab::TeleportF8,
                         HotkeyAction::MpMenu => self.current_tab = MainTab::MpF9,
                         HotkeyAction::Reload => restart_app(),
@@ -995,6 +1458,17 @@ impl eframe::App for MyApp {
                     ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
                 }
 
+                while let Ok(update) = self.rules_receiver.try_recv() {
+                    self.apply_rules_update(update);
+                }
+                self.show_bind_prompt(ctx);
+                for mut entry in self.batch_queue.take_sent_entries() {
+                    entry.stamp_now();
+                    journal::append_entry(&entry);
+                    self.journal.push(entry);
+                }
+                if self.batch_queue.is_running() { ctx.request_repaint_after(Duration::from_millis(200)); }
+
                 egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
                     ui.horizontal(|ui| {
                         ui.label(egui::RichText::new("🛡 AdminHelper").strong().color(accent_color).size(16.0));
@@ -1003,6 +1477,7 @@ impl eframe::App for MyApp {
                         ui.selectable_value(&mut self.current_tab, MainTab::PunishF7, "Наказания");
                         ui.selectable_value(&mut self.current_tab, MainTab::TeleportF8, "События");
                         ui.selectable_value(&mut self.current_tab, MainTab::MpF9, "Мероприятие");
+                        ui.selectable_value(&mut self.current_tab, MainTab::Journal, "📒 Журнал");
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             if ui.button("⚙ Настройки").clicked() { self.current_tab = MainTab::Setup; }
                         });
@@ -1011,6 +1486,25 @@ impl eframe::App for MyApp {
                 });
 
 
+                if !self.config.admin_level_set {
+                    egui::Window::new("👤 Уровень админки").collapsible(false).resizable(false).anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0]).show(ctx, |ui| {
+                        ui.label("Укажите свой уровень: от него зависит, какие команды, наказания и пресеты доступны.");
+                        ui.label(egui::RichText::new("Пока уровень не выбран, программа считает вас модератором.").weak().size(12.0));
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("first_admin_level").selected_text(data::level_label(self.config.admin_level)).show_ui(ui, |ui| {
+                                for level in 0..=data::MAX_ADMIN_LEVEL { ui.selectable_value(&mut self.config.admin_level, level, data::level_label(level)); }
+                            });
+                            if ui.button("Сохранить").clicked() {
+                                self.config.admin_level_set = true;
+                                save_config(&self.config);
+                                self.update_punish_command();
+                                log(&format!("Config: admin level set to {}", data::level_label(self.config.admin_level)));
+                            }
+                        });
+                        ui.label(egui::RichText::new("Изменить можно в настройках → «👤 Администратор».").weak().size(12.0));
+                    });
+                }
+
                 egui::TopBottomPanel::bottom("bottom_bar").show(ctx, |ui| {
                     ui.horizontal(|ui| {
                         
@@ -1107,6 +1601,24 @@ impl eframe::App for MyApp {
                                     });
                                 });
 
+                                ui.add_space(15.0);
+                                ui.group(|ui| {
+                                    ui.heading("👤 Администратор");
+                                    ui.horizontal(|ui| {
+                                        ui.label("Статический ID:");
+                                        if ui.add(egui::TextEdit::singleline(&mut self.config.admin_id).desired_width(100.0)).on_hover_text("Для пресетов МП и подстановки {admin_id} в биндах").changed() { save_config(&self.config); self.sync_hotstring_context(); }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Ваш уровень:");
+                                        let before = self.config.admin_level;
+                                        egui::ComboBox::from_id_source("admin_level").selected_text(data::level_label(self.config.admin_level)).show_ui(ui, |ui| {
+                                            for level in 0..=data::MAX_ADMIN_LEVEL { ui.selectable_value(&mut self.config.admin_level, level, data::level_label(level)); }
+                                        });
+                                        if self.config.admin_level != before { self.config.admin_level_set = true; save_config(&self.config); self.update_punish_command(); }
+                                    });
+                                    ui.label(egui::RichText::new("Команды выше вашего уровня скрываются в F6 и блокируются в наказаниях, МП и выдаче рангов.").weak().size(12.0));
+                                });
+
                                 ui.add_space(15.0);
                                 ui.group(|ui| {
                                     ui.heading("⌨ Горячие клавиши");
@@ -1137,6 +1649,58 @@ impl eframe::App for MyApp {
                                         ui.end_row();
                                     });
                                 });
+                                ui.add_space(15.0);
+                                ui.group(|ui| {
+                                    ui.heading("📝 Причина наказания");
+                                    ui.label("Шаблон причины в командах F7. {имя} - подстановка, [ ... ] - кусок, который пропадает, если подстановка внутри пустая.");
+                                    let response = ui.add(egui::TextEdit::singleline(&mut self.config.reason_template).desired_width(f32::INFINITY));
+                                    let check = reason::validate(&self.config.reason_template);
+                                    if response.changed() && check.is_ok() { save_config(&self.config); self.update_punish_command(); }
+                                    ui.horizontal_wrapped(|ui| {
+                                        for (name, hint) in reason::PLACEHOLDERS {
+                                            if ui.small_button(format!("{{{}}}", name)).on_hover_text(hint).clicked() { self.config.reason_template.push_str(&format!(" {{{}}}", name)); if reason::validate(&self.config.reason_template).is_ok() { save_config(&self.config); self.update_punish_command(); } }
+                                        }
+                                        if ui.small_button("По умолчанию").clicked() { self.config.reason_template = default_reason_template(); save_config(&self.config); self.update_punish_command(); }
+                                    });
+                                    match check {
+                                        Ok(()) => {
+                                            let sample = format!("/ban 12156 3d {}", reason::render(&self.config.reason_template, &ReasonFields::sample(&self.config.admin_name)));
+                                            let len = sample.chars().count();
+                                            ui.label(egui::RichText::new(format!("Пример: {}", sample)).color(accent_color));
+                                            let counter = egui::RichText::new(format!("Длина: {} / {}", len, reason::CHAT_LIMIT));
+                                            ui.label(if len > reason::CHAT_LIMIT { counter.color(egui::Color32::RED) } else { counter.weak() });
+                                        },
+                                        Err(e) => { ui.label(egui::RichText::new(format!("❌ Ошибка в шаблоне: {} (используется стандартный)", e)).color(egui::Color32::RED)); },
+                                    }
+                                });
+                                ui.add_space(15.0);
+                                ui.group(|ui| {
+                                    ui.heading("📨 Ответы на жалобы");
+                                    ui.label("Текст, который F7 копирует для форума или Discord. Кроме подстановок причины доступны:");
+                                    ui.horizontal_wrapped(|ui| {
+                                        for (name, hint) in reason::RESPONSE_PLACEHOLDERS { ui.label(egui::RichText::new(format!("{{{}}}", name)).monospace()).on_hover_text(hint); }
+                                    });
+                                    let mut changed = false;
+                                    let mut remove = None;
+                                    for (i, t) in self.config.response_templates.iter_mut().enumerate() {
+                                        ui.separator();
+                                        ui.horizontal(|ui| {
+                                            ui.label("Название:"); changed |= ui.text_edit_singleline(&mut t.name).changed();
+                                            if ui.button("🗑").clicked() { remove = Some(i); }
+                                        });
+                                        changed |= ui.add(egui::TextEdit::multiline(&mut t.text).desired_rows(3).desired_width(f32::INFINITY)).changed();
+                                        match reason::validate_response(&t.text) {
+                                            Ok(()) => { ui.label(egui::RichText::new(reason::render_response(&t.text, &ReasonFields::sample(&self.config.admin_name))).color(accent_color)); },
+                                            Err(e) => { ui.label(egui::RichText::new(format!("❌ Ошибка в шаблоне: {}", e)).color(egui::Color32::RED)); },
+                                        }
+                                    }
+                                    if let Some(i) = remove { self.config.response_templates.remove(i); changed = true; }
+                                    ui.horizontal(|ui| {
+                                        if ui.button("➕ Добавить").clicked() { self.config.response_templates.push(ResponseTemplate { name: "Новый".to_string(), text: "Жалоба одобрена, {article}.".to_string() }); changed = true; }
+                                        if ui.button("По умолчанию").clicked() { self.config.response_templates = default_response_templates(); changed = true; }
+                                    });
+                                    if changed { save_config(&self.config); }
+                                });
                                 ui.add_space(30.0);
                                 ui.vertical_centered(|ui| {
                                     
@@ -1146,6 +1710,71 @@ impl eframe::App for MyApp {
                                 });
                             });
                         },
+                        MainTab::Journal => {
+                            ui.heading("📒 Журнал наказаний");
+                            if let Some(target) = self.revoke_target.clone() {
+                                ui.group(|ui| {
+                                    ui.label(egui::RichText::new(format!("↩ Снятие: ID {}, {}, {} ({})", target.player_id, target.article, target.punishment_text(), target.time)).strong().color(accent_color));
+                                    match punish::reversal_for_command(&target.command) {
+                                        Some(reversal) => {
+                                            ui.horizontal(|ui| { ui.label("Обжалование / ЖБ №:"); ui.add(egui::TextEdit::singleline(&mut self.revoke_appeal).desired_width(100.0).hint_text("пусто - ошибка выдачи")); });
+                                            let reason = journal::revoke_reason(&target.article, &self.revoke_appeal);
+                                            let command = reversal.command(&target.player_id, &reason);
+                                            ui.monospace(&command);
+                                            ui.label(egui::RichText::new(format!("{} {}", reversal.cmd_base, reversal.hint)).weak());
+                                            let level = data::check_level(&command, self.config.admin_level);
+                                            if let Err(e) = &level { ui.label(egui::RichText::new(format!("⛔ {}", e)).color(egui::Color32::RED)); }
+                                            ui.horizontal(|ui| {
+                                                if ui.add_enabled(level.is_ok(), egui::Button::new("🚀 Снять")).clicked() {
+                                                    send_command(Some(ctx.clone()), command.clone(), "Журнал");
+                                                    let mut entry = target.revocation(command, &reason, &self.config.admin_name);
+                                                    entry.stamp_now();
+                                                    journal::append_entry(&entry);
+                                                    self.journal.push(entry);
+                                                    self.revoke_target = None;
+                                                }
+                                                if ui.button("Отмена").clicked() { self.revoke_target = None; }
+                                            });
+                                        },
+                                        None => {
+                                            ui.label("Для этой команды нет обратной - снимите наказание вручную.");
+                                            if ui.button("Закрыть").clicked() { self.revoke_target = None; }
+                                        },
+                                    }
+                                });
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("ID:"); ui.add(egui::TextEdit::singleline(&mut self.journal_filter.player_id).desired_width(70.0));
+                                ui.label("Статья:"); ui.add(egui::TextEdit::singleline(&mut self.journal_filter.article).desired_width(70.0));
+                                for period in [JournalPeriod::Today, JournalPeriod::Week, JournalPeriod::All] { ui.selectable_value(&mut self.journal_filter.period, period, period.label()); }
+                            });
+                            let shown: Vec<&JournalEntry> = self.journal.iter().rev().filter(|e| self.journal_filter.matches(e)).collect();
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Записей: {}", shown.len()));
+                                if ui.button("📋 Отчет за смену").clicked() {
+                                    let mut chronological = shown.clone(); chronological.reverse();
+                                    if let Ok(mut clipboard) = Clipboard::new() { let _ = clipboard.set_text(journal::shift_report(&chronological, &self.config.admin_name, self.journal_filter.period)); }
+                                }
+                            });
+                            ui.separator();
+                            egui::ScrollArea::vertical().id_source("journal_scroll").show(ui, |ui| {
+                                if shown.is_empty() { ui.label("Записей нет. Наказания, выданные кнопкой «🚀 Выдать» в F7, попадают сюда."); }
+                                egui::Grid::new("journal_grid").striped(true).spacing([12.0, 4.0]).show(ui, |ui| {
+                                    for h in ["Время", "ID", "Статья", "Наказание", "ЖБ", "Админ", ""] { ui.label(egui::RichText::new(h).strong()); }
+                                    ui.end_row();
+                                    for e in &shown {
+                                        let revoked = journal::is_revoked(&self.journal, e);
+                                        let punishment = egui::RichText::new(e.punishment_text());
+                                        ui.label(&e.time); ui.label(&e.player_id); ui.label(&e.article).on_hover_text(&e.category); ui.label(if revoked { punishment.strikethrough() } else { punishment }).on_hover_text(&e.command); ui.label(&e.report); ui.label(&e.admin);
+                                        if revoked { ui.label(egui::RichText::new("снято").weak()); }
+                                        else if e.revokes.is_empty() && punish::reversal_for_command(&e.command).is_some() {
+                                            if ui.small_button("↩ Снять").clicked() { self.revoke_target = Some((*e).clone()); self.revoke_appeal.clear(); }
+                                        } else { ui.label(""); }
+                                        ui.end_row();
+                                    }
+                                });
+                            });
+                        },
                         MainTab::Logs => {
                              ui.heading("Диагностика и Логи");
                              egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
@@ -1158,6 +1787,7 @@ impl eframe::App for MyApp {
                             ui.horizontal(|ui| {
                                 ui.selectable_value(&mut self.f6_tab, F6Tab::Description, "Описание");
                                 ui.selectable_value(&mut self.f6_tab, F6Tab::Commands, "Команды");
+                                ui.selectable_value(&mut self.f6_tab, F6Tab::History, "История");
                                 ui.selectable_value(&mut self.f6_tab, F6Tab::AutoReplace, "Автозамены");
                                 ui.selectable_value(&mut self.f6_tab, F6Tab::Events, "Мероприятия");
                                 ui.selectable_value(&mut self.f6_tab, F6Tab::OrgManager, "Организация");
@@ -1190,32 +1820,113 @@ impl eframe::App for MyApp {
                                     });
                                 },
                                 F6Tab::Commands => {
-                                    ui.horizontal(|ui| { ui.label("Поиск:"); ui.text_edit_singleline(&mut self.cmd_search); });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Поиск:"); ui.add(egui::TextEdit::singleline(&mut self.cmd_search).hint_text("имя, сокращение или описание"));
+                                        ui.checkbox(&mut self.show_locked_commands, "Показывать недоступные");
+                                        if ui.checkbox(&mut self.config.prefer_short_commands, "Отправлять сокращения").on_hover_text("/mc вместо /mutechat, /spec вместо /spectate").changed() { save_config(&self.config); }
+                                    });
                                     
                                     egui::ScrollArea::vertical().id_source("f6_cmd_scroll").show(ui, |ui| {
-                                        for (cmd, desc) in data::get_admin_commands() {
+                                        for command in data::admin_commands() {
+                                            let (cmd, desc) = (command.name, command.desc);
+                                            let allowed = command.level <= self.config.admin_level;
+                                            if !allowed && !self.show_locked_commands { continue; }
                                             // Фильтр поиска
-                                            if self.cmd_search.is_empty() || cmd.to_lowercase().contains(&self.cmd_search.to_lowercase()) {
-                                                
-                                                // === НОВАЯ ЛОГИКА ===
-                                                // Проверяем описание: если есть '[', значит нужны аргументы.
-                                                let needs_args = desc.contains('[');
-                                                
-                                                // Если нужны аргументы -> Enter НЕ жмем (false), иначе жмем (true)
-                                                let press_enter = !needs_args;
-                                                
-                                                // Если нужны аргументы -> добавляем пробел в конце, чтобы сразу писать ID
-                                                let text_to_type = if needs_args { format!("{} ", cmd) } else { cmd.to_string() };
-
-                                                // === СТАРЫЙ СТИЛЬ ===
-                                                // Обычная кнопка с форматом "Команда - Описание"
-                                                if ui.button(format!("{} - {}", cmd, desc)).clicked() { 
-                                                    type_in_game(Some(ctx.clone()), text_to_type, true, press_enter, None); 
+                                            if command.matches(&self.cmd_search) {
+                                                // Команда без аргументов уходит сразу, с аргументами - открывает форму
+                                                let button = ui.add_enabled(allowed, egui::Button::new(format!("{} - {} ({})", commands::signature(command), desc, data::level_label(command.level))));
+                                                if let Some(alias) = command.matched_alias(&self.cmd_search) { ui.label(egui::RichText::new(format!("{} → {}", alias, cmd)).color(accent_color).size(12.0)); }
+                                                if button.on_disabled_hover_text(format!("Доступна с уровня {}", data::level_label(command.level))).clicked() { 
+                                                    if command.args.is_empty() {
+                                                        send_command(Some(ctx.clone()), command.send_name(self.config.prefer_short_commands).to_string(), "F6");
+                                                    } else if self.cmd_form.as_ref().map(|(name, _)| *name) == Some(cmd) {
+                                                        self.cmd_form = None;
+                                                    } else {
+                                                        self.cmd_form = Some((cmd, vec![String::new(); command.args.len()]));
+                                                    }
+                                                }
+                                                let mut send = None;
+                                                if let Some((_, values)) = self.cmd_form.as_mut().filter(|(name, _)| *name == cmd) {
+                                                    ui.indent(("cmd_form", cmd), |ui| {
+                                                        let errors = commands::check_form(command, values);
+                                                        egui::Grid::new(("cmd_form_grid", cmd)).num_columns(3).spacing([10.0, 5.0]).show(ui, |ui| {
+                                                            for (i, arg) in command.args.iter().enumerate() {
+                                                                ui.label(format!("{}:", arg.name));
+                                                                let width = if arg.kind == data::ArgKind::Reason { 250.0 } else { 120.0 };
+                                                                ui.add(egui::TextEdit::singleline(&mut values[i]).desired_width(width).hint_text(commands::arg_hint(arg.kind)));
+                                                                // Пустые поля не краснеют, пока их не начали заполнять
+                                                                match errors.iter().find(|(j, _)| *j == i) {
+                                                                    Some((_, e)) if !values[i].trim().is_empty() => { ui.label(egui::RichText::new(e).color(egui::Color32::RED)); },
+                                                                    _ => { ui.label(""); },
+                                                                }
+                                                                ui.end_row();
+                                                            }
+                                                        });
+                                                        let line = commands::build_command(command, values, self.config.prefer_short_commands);
+                                                        ui.horizontal(|ui| {
+                                                            if let Some(line) = &line { ui.monospace(line); }
+                                                            if ui.add_enabled(line.is_some(), egui::Button::new("🚀 Отправить")).clicked() { send = line.clone(); }
+                                                        });
+                                                    });
+                                                }
+                                                if let Some(line) = send {
+                                                    send_command(Some(ctx.clone()), line, "F6");
+                                                    self.cmd_form = None;
                                                 }
                                             }
                                         }
                                     });
                                 },
+                                F6Tab::History => {
+                                    if !self.config.pinned_commands.is_empty() {
+                                        ui.label(egui::RichText::new("📌 Закрепленные").strong());
+                                        let mut unpin = None;
+                                        ui.horizontal_wrapped(|ui| {
+                                            for (i, cmd) in self.config.pinned_commands.iter().enumerate() {
+                                                let allowed = data::check_level(cmd, self.config.admin_level);
+                                                let button = ui.add_enabled(allowed.is_ok(), egui::Button::new(cmd)).on_hover_text("ПКМ - открепить").on_disabled_hover_text(allowed.err().unwrap_or_default());
+                                                if button.clicked() { send_command(Some(ctx.clone()), cmd.clone(), "Закреплено"); }
+                                                if button.secondary_clicked() { unpin = Some(i); }
+                                            }
+                                        });
+                                        if let Some(i) = unpin { self.config.pinned_commands.remove(i); save_config(&self.config); }
+                                        ui.separator();
+                                    }
+                                    ui.horizontal(|ui| {
+                                        ui.label("✏");
+                                        ui.add(egui::TextEdit::singleline(&mut self.history_edit).desired_width(ui.available_width() - 200.0).hint_text("команда для правки и отправки"));
+                                        let allowed = data::check_level(&self.history_edit, self.config.admin_level);
+                                        let command = self.history_edit.trim().to_string();
+                                        if ui.add_enabled(!command.is_empty() && allowed.is_ok(), egui::Button::new("🚀 Отправить")).on_disabled_hover_text(allowed.err().unwrap_or_default()).clicked() {
+                                            send_command(Some(ctx.clone()), command.clone(), "История");
+                                            self.history_edit.clear();
+                                        }
+                                        if ui.add_enabled(!command.is_empty(), egui::Button::new("📌")).on_hover_text("Закрепить").clicked() && !self.config.pinned_commands.contains(&command) {
+                                            self.config.pinned_commands.push(command); save_config(&self.config);
+                                        }
+                                    });
+                                    ui.horizontal(|ui| { ui.label("🔎 Поиск:"); ui.text_edit_singleline(&mut self.history_search); });
+                                    ui.separator();
+                                    let entries = history::search(&self.history_search);
+                                    if entries.is_empty() { ui.label("История пуста. Сюда попадают все команды, отправленные из меню."); }
+                                    egui::ScrollArea::vertical().id_source("f6_history_scroll").show(ui, |ui| {
+                                        egui::Grid::new("history_grid").striped(true).num_columns(4).spacing([10.0, 4.0]).show(ui, |ui| {
+                                            for entry in entries.iter().take(200) {
+                                                ui.label(egui::RichText::new(&entry.time).weak());
+                                                ui.label(&entry.origin);
+                                                ui.monospace(&entry.command);
+                                                ui.horizontal(|ui| {
+                                                    let allowed = data::check_level(&entry.command, self.config.admin_level);
+                                                    if ui.add_enabled(allowed.is_ok(), egui::Button::new("🔁")).on_hover_text("Отправить еще раз").on_disabled_hover_text(allowed.err().unwrap_or_default()).clicked() { send_command(Some(ctx.clone()), entry.command.clone(), "История"); }
+                                                    if ui.button("✏").on_hover_text("Изменить и отправить").clicked() { self.history_edit = entry.command.clone(); }
+                                                    let pinned = self.config.pinned_commands.contains(&entry.command);
+                                                    if ui.add_enabled(!pinned, egui::Button::new("📌")).on_hover_text("Закрепить").clicked() { self.config.pinned_commands.push(entry.command.clone()); save_config(&self.config); }
+                                                });
+                                                ui.end_row();
+                                            }
+                                        });
+                                    });
+                                },
                                 F6Tab::AutoReplace => {
                                     
                                     ui.horizontal(|ui| {
@@ -1229,6 +1940,13 @@ impl eframe::App for MyApp {
                                         });
                                     });
                                     
+                                    let notes = self.active_replacements.lock().map(|r| conflict_notes(&r, &bind_conflicts(&r))).unwrap_or_default();
+                                    if let Some(error) = &self.bind_save_error {
+                                        ui.colored_label(egui::Color32::RED, error);
+                                    } else if !notes.is_empty() {
+                                        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "⚠ Есть бинды, которые перекрывают друг друга - наведите на ⚠, чтобы увидеть, какие");
+                                    }
+
                                     ui.separator();
                                     ui.add_space(5.0);
 
@@ -1257,22 +1975,38 @@ impl eframe::App for MyApp {
                                                     ui.add(egui::TextEdit::multiline(&mut self.new_rep_text).desired_width(f32::INFINITY).desired_rows(3));
                                                     ui.end_row();
                                                 });
+                                                ui.horizontal_wrapped(|ui| {
+                                                    ui.label(egui::RichText::new("Подстановки:").weak().size(12.0));
+                                                    for (name, hint) in hotstring::PLACEHOLDERS {
+                                                        if ui.small_button(format!("{{{}}}", name)).on_hover_text(hint).clicked() { self.new_rep_text.push_str(&format!("{{{}}}", name)); }
+                                                    }
+                                                    if ui.small_button("{?ID}").on_hover_text("Поле-запрос: при срабатывании появится окно для ввода значения. Имя можно любое: {?Срок}, {?Причина}").clicked() { self.new_rep_text.push_str("{?ID}"); }
+                                                });
 
                                                 ui.add_space(5.0);
                                                 ui.vertical_centered_justified(|ui| {
-                                                    if ui.button("Добавить").clicked() {
-                                                        if !self.new_rep_trigger.is_empty() && !self.new_rep_text.is_empty() {
-                                                            if let Ok(mut replacements) = self.active_replacements.lock() {
-                                                                replacements.push(ActiveReplacement {
-                                                                    trigger: self.new_rep_trigger.clone(),
-                                                                    label: self.new_rep_label.clone(),
-                                                                    text: self.new_rep_text.clone(),
-                                                                    is_system: false,
-                                                                });
+                                                    if ui.button("Добавить").clicked() && !self.new_rep_trigger.is_empty() && !self.new_rep_text.is_empty() {
+                                                        let mut added = false;
+                                                        if let Ok(mut replacements) = self.active_replacements.lock() {
+                                                            replacements.push(ActiveReplacement {
+                                                                trigger: self.new_rep_trigger.clone(),
+                                                                label: self.new_rep_label.clone(),
+                                                                text: self.new_rep_text.clone(),
+                                                                options: BindOptions::default(),
+                                                                is_system: false,
+                                                            });
+                                                            // Новый бинд, который перекрывается с имеющимися, не добавляем
+                                                            let new_idx = replacements.len() - 1;
+                                                            let notes = conflict_notes(&replacements, &bind_conflicts(&replacements));
+                                                            if let Some(problems) = notes.get(&new_idx) {
+                                                                self.bind_save_error = Some(format!("«{}» не добавлен. {}", self.new_rep_trigger, problems.join("; ")));
+                                                                replacements.pop();
+                                                            } else {
                                                                 self.new_rep_trigger.clear(); self.new_rep_label.clear(); self.new_rep_text.clear();
+                                                                added = true;
                                                             }
-                                                            self.save_triggers();
                                                         }
+                                                        if added { self.save_triggers(); }
                                                     }
                                                 });
                                             });
@@ -1305,6 +2039,8 @@ impl eframe::App for MyApp {
                                                             ui.horizontal(|ui| { 
                                                                 ui.label("Бинд:"); 
                                                                 ui.add(egui::TextEdit::singleline(&mut rep.trigger).desired_width(50.0)); 
+                                                                bind_options_menu(ui, &mut rep.options);
+                                                                if let Some(problems) = notes.get(&idx) { ui.colored_label(egui::Color32::RED, "⚠").on_hover_text(problems.join("\n")); }
                                                             });
                                                             
                                                             
@@ -1353,7 +2089,7 @@ impl eframe::App for MyApp {
                                                         ui.end_row();
                                                         
                                                         if let Ok(mut replacements) = self.active_replacements.lock() {
-                                                            for rep in replacements.iter_mut() {
+                                                            for (idx, rep) in replacements.iter_mut().enumerate() {
                                                                 if !rep.is_system { continue; }
                                                                 
                                                                 if !self.replace_search.is_empty() && !rep.label.to_lowercase().contains(&self.replace_search.to_lowercase()) {
@@ -1361,7 +2097,11 @@ impl eframe::App for MyApp {
                                                                 }
 
                                                                 
-                                                                ui.add(egui::TextEdit::singleline(&mut rep.trigger).desired_width(width_bind).hint_text("..."));
+                                                                ui.horizontal(|ui| {
+                                                                    ui.add(egui::TextEdit::singleline(&mut rep.trigger).desired_width(width_bind).hint_text("..."));
+                                                                    bind_options_menu(ui, &mut rep.options);
+                                                                    if let Some(problems) = notes.get(&idx) { ui.colored_label(egui::Color32::RED, "⚠").on_hover_text(problems.join("\n")); }
+                                                                });
                                                                 
                                                                 
                                                                 ui.add_sized([width_desc, 20.0], egui::Label::new(&rep.label).truncate(true));
@@ -1479,15 +2219,18 @@ impl eframe::App for MyApp {
                                             egui::Color32::WHITE 
                                         };
 
-                                        if ui.add_sized(
+                                        // Панель шлет /setfactionrank - в каталоге это второе написание /setfractionrank
+                                        let rank_level = data::check_level("/setfactionrank", self.config.admin_level);
+                                        if let Err(e) = &rank_level { ui.label(egui::RichText::new(format!("⛔ {}", e)).color(egui::Color32::RED)); }
+                                        if ui.add_enabled_ui(rank_level.is_ok(), |ui| ui.add_sized(
                                             [180.0, 35.0], 
                                             egui::Button::new(egui::RichText::new("ВЫДАТЬ РАНГ").strong().color(btn_text_color))
-                                        ).clicked() {
+                                        )).inner.clicked() {
                                             let org = &self.orgs[self.selected_org_index];
                                             if !org.ranks.is_empty() {
                                                 let rank = &org.ranks[self.selected_rank_index];
                                                 let cmd = format!("/setfactionrank {} {} {}", self.org_input_id, org.key, rank.id);
-                                                type_in_game(Some(ctx.clone()), cmd, true, true, None);
+                                                send_command(Some(ctx.clone()), cmd, "Ранги");
                                             }
                                         }
                                     });
@@ -1583,40 +2326,339 @@ impl eframe::App for MyApp {
                             }
                         },
                         MainTab::PunishF7 => {
-                            ui.columns(2, |columns| {
-                                columns[0].vertical(|ui| {
-                                    ui.horizontal(|ui| { ui.label("🔎"); ui.text_edit_singleline(&mut self.search_text); if !self.search_text.is_empty() && ui.button("X").clicked() { self.search_text.clear(); } }); ui.separator();
-                                    egui::ScrollArea::vertical().id_source("f7_list_scroll").show(ui, |ui| {
-                                        let query = self.search_text.to_lowercase();
-                                        let mut picked_rule: Option<Rule> = None;
-                                        for rule in &self.rules {
-                                            if query.is_empty() || rule.article.to_lowercase().contains(&query) || rule.title.to_lowercase().contains(&query) {
-                                                if ui.add_sized([ui.available_width(), 20.0], egui::Button::new(format!("{} - {}", rule.article, rule.title))).clicked() { picked_rule = Some(rule.clone()); }
-                                            }
+                            if self.show_rules_diff {
+                                ui.horizontal(|ui| {
+                                    if ui.button("⬅ Назад").clicked() { self.show_rules_diff = false; }
+                                    ui.heading(format!("Изменения регламента: {} → {}", self.rules_prev_title.clone().unwrap_or_default(), self.rules_title));
+                                });
+                                ui.separator();
+                                let added = egui::Color32::from_rgb(0, 200, 100);
+                                let removed = egui::Color32::from_rgb(220, 50, 50);
+                                egui::ScrollArea::vertical().id_source("f7_diff_scroll").show(ui, |ui| {
+                                    if self.rules_diff.is_empty() { ui.label("Статьи не изменились."); }
+                                    for (i, change) in self.rules_diff.iter().enumerate() {
+                                        match change {
+                                            RuleChange::Added(r) => { ui.label(egui::RichText::new(format!("➕ [{}] {} - {}", r.category, r.article, r.title)).color(added)); },
+                                            RuleChange::Removed(r) => { ui.label(egui::RichText::new(format!("➖ [{}] {} - {}", r.category, r.article, r.title)).strikethrough().color(removed)); },
+                                            RuleChange::Changed { rule, fields } => {
+                                                let tiers_changed = fields.iter().any(|f| f.is_punishment);
+                                                let title = format!("{} [{}] {} - {}", if tiers_changed { "⚖" } else { "✏" }, rule.category, rule.article, rule.title);
+                                                egui::CollapsingHeader::new(egui::RichText::new(title).color(if tiers_changed { accent_color } else { ui.visuals().text_color() }))
+                                                    .id_source(("rule_diff", i))
+                                                    .show(ui, |ui| {
+                                                        for f in fields {
+                                                            ui.horizontal_wrapped(|ui| {
+                                                                let name = egui::RichText::new(format!("{}:", f.field)).strong();
+                                                                ui.label(if f.is_punishment { name.color(accent_color) } else { name });
+                                                                ui.label(egui::RichText::new(if f.old.is_empty() { "—" } else { &f.old }).strikethrough().color(removed));
+                                                                ui.label("→");
+                                                                ui.label(egui::RichText::new(if f.new.is_empty() { "—" } else { &f.new }).color(added));
+                                                            });
+                                                        }
+                                                    });
+                                            },
                                         }
-                                        if let Some(rule) = picked_rule { self.selected_rule = Some(rule); self.selected_punishment_idx = 0; self.update_punish_command(); }
-                                    });
+                                    }
                                 });
-                                columns[1].vertical(|ui| {
-                                    let current_rule = self.selected_rule.clone();
-                                    if let Some(rule) = current_rule {
-                                        ui.heading(format!("{} {}", rule.article, rule.title)); ui.separator();
-                                        egui::ScrollArea::vertical().id_source("f7_desc_scroll").max_height(300.0).show(ui, |ui| { let clean_desc = rule.description.replace("`n", "\n");ui.label(egui::RichText::new(clean_desc).italics()); }); ui.separator();
-                                        egui::Grid::new("punish_inputs").spacing([10.0, 10.0]).show(ui, |ui| {
-                                            ui.label("ID:"); if ui.add(egui::TextEdit::singleline(&mut self.input_id).desired_width(100.0)).changed() { self.update_punish_command(); } ui.end_row();
-                                            ui.label("Время:"); if ui.add(egui::TextEdit::singleline(&mut self.input_violation_time).desired_width(100.0)).changed() { self.update_punish_command(); } ui.end_row();
-                                            ui.label("ЖБ:"); if ui.add(egui::TextEdit::singleline(&mut self.input_report_num).desired_width(100.0)).changed() { self.update_punish_command(); } ui.end_row();
-                                        }); ui.separator();
-                                        let options = Self::get_rule_options(&rule);
-                                        for (i, opt) in options.iter().enumerate() { if ui.radio_value(&mut self.selected_punishment_idx, i, &opt.label).changed() { self.update_punish_command(); } } ui.separator();
-                                        ui.add_sized([ui.available_width(), 30.0], egui::TextEdit::multiline(&mut self.generated_punish_cmd));
+                            } else {
+                                ui.columns(2, |columns| {
+                                    columns[0].vertical(|ui| {
                                         ui.horizontal(|ui| {
-                                            if ui.button("📋 Копировать").clicked() { if let Ok(mut clipboard) = Clipboard::new() { let _ = clipboard.set_text(self.generated_punish_cmd.clone()); } }
-                                            if ui.button("🚀 Выдать (Enter)").clicked() { type_in_game(Some(ctx.clone()), self.generated_punish_cmd.clone(), true, true, None); }
+                                            ui.label(egui::RichText::new(format!("📜 Регламент {}", self.rules_title)).weak());
+                                            if self.rules_prev_title.is_some() && ui.button(format!("Изменения ({})", self.rules_diff.len())).clicked() { self.show_rules_diff = true; }
+                                        });
+                                        let search_field = ui.horizontal(|ui| {
+                                            ui.label("🔎");
+                                            let field = ui.add(egui::TextEdit::singleline(&mut self.search_text).hint_text("↑↓ - выбор, Enter - открыть"));
+                                            if !self.search_text.is_empty() && ui.button("X").clicked() { self.search_text.clear(); }
+                                            field
+                                        }).inner;
+                                        if std::mem::take(&mut self.focus_search) { search_field.request_focus(); }
+                                        if search_field.changed() { self.rule_cursor = 0; }
+                                        ui.checkbox(&mut self.only_punishable, "Только статьи с наказанием"); ui.separator();
+                                        // Стрелки ходят по результатам поиска, а без запроса - по избранному и недавним
+                                        // и дальше по статьям раскрытых категорий
+                                        let searching = !self.search_text.trim().is_empty();
+                                        let mut hits = if searching { self.search_rules() } else { Vec::new() };
+                                        if self.only_punishable { hits.retain(|h| punish::has_punishment(&self.rules[h.index])); }
+                                        let quick = if searching { Vec::new() } else { self.quick_rules() };
+                                        let tree: Vec<(&str, usize, Vec<usize>)> = if searching { Vec::new() } else {
+                                            self.rule_tree.iter()
+                                                .map(|c| (c.name.as_str(), c.rules.len(), c.rules.iter().copied().filter(|&i| !self.only_punishable || punish::has_punishment(&self.rules[i])).collect::<Vec<_>>()))
+                                                .filter(|(_, _, shown)| !shown.is_empty())
+                                                .collect()
+                                        };
+                                        let nav: Vec<usize> = if searching { hits.iter().map(|h| h.index).collect() } else {
+                                            let open = tree.iter().filter(|(name, _, _)| self.open_rule_categories.iter().any(|o| o == name));
+                                            quick.iter().copied().chain(open.flat_map(|(_, _, shown)| shown.iter().copied())).collect()
+                                        };
+                                        let mut cursor_moved = false;
+                                        if search_field.has_focus() && !nav.is_empty() {
+                                            if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown)) { self.rule_cursor = (self.rule_cursor + 1).min(nav.len() - 1); cursor_moved = true; }
+                                            if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp)) { self.rule_cursor = self.rule_cursor.saturating_sub(1); cursor_moved = true; }
+                                        }
+                                        self.rule_cursor = self.rule_cursor.min(nav.len().saturating_sub(1));
+                                        // Статья может быть и в недавних, и в дереве - подсвечиваем по позиции в списке
+                                        let cursor_pos = if search_field.has_focus() || search_field.lost_focus() { Some(self.rule_cursor) } else { None };
+                                        let cursor_rule = cursor_pos.and_then(|pos| nav.get(pos).copied());
+                                        let mut picked_rule: Option<Rule> = None;
+                                        if search_field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                            if let Some(i) = cursor_rule { picked_rule = Some(self.rules[i].clone()); self.focus_punish_id = true; }
+                                        }
+                                        if let Some(notice) = &self.rules_notice {
+                                            let mut close = false;
+                                            ui.horizontal_wrapped(|ui| {
+                                                ui.label(egui::RichText::new(format!("ℹ {}", notice)).color(accent_color));
+                                                close = ui.small_button("✖").clicked();
+                                            });
+                                            if close { self.rules_notice = None; }
+                                            ui.separator();
+                                        }
+                                        if let Some(e) = &self.rules_load_error {
+                                            ui.group(|ui| {
+                                                ui.label(egui::RichText::new("❌ Файл правил не загружен").strong().color(egui::Color32::RED));
+                                                ui.label(e.describe());
+                                                ui.label(egui::RichText::new("Используется предыдущая версия правил. Исправьте файл и сохраните - он перезагрузится сам.").weak().size(12.0));
+                                            });
+                                            ui.separator();
+                                        }
+                                        if !self.rule_errors.is_empty() {
+                                            ui.collapsing(egui::RichText::new(format!("⚠ Ошибки в rules.json ({})", self.rule_errors.len())).color(egui::Color32::RED), |ui| {
+                                                for e in &self.rule_errors { ui.label(e); }
+                                            });
+                                            ui.separator();
+                                        }
+                                        egui::ScrollArea::vertical().id_source("f7_list_scroll").show(ui, |ui| {
+                                            if !searching && !quick.is_empty() {
+                                                ui.label(egui::RichText::new("★ Избранное и недавние").weak());
+                                                for (pos, &i) in quick.iter().enumerate() {
+                                                    let rule = &self.rules[i];
+                                                    let star = if self.config.favorite_rules.contains(&rule.article) { "★" } else { "🕑" };
+                                                    let button = ui.add_sized([ui.available_width(), 20.0], egui::Button::new(format!("{} {} - {}", star, rule.article, rule.title)).selected(cursor_pos == Some(pos)));
+                                                    if cursor_moved && cursor_pos == Some(pos) { button.scroll_to_me(None); }
+                                                    if button.clicked() { picked_rule = Some(rule.clone()); }
+                                                }
+                                                ui.separator();
+                                            }
+                                            if !searching {
+                                                let mut next_pos = quick.len();
+                                                let mut open_now = Vec::new();
+                                                for (category, total, shown) in &tree {
+                                                    // Позиции в nav есть только у категорий, раскрытых с прошлого кадра
+                                                    let first_pos = if self.open_rule_categories.iter().any(|o| o == category) { next_pos += shown.len(); Some(next_pos - shown.len()) } else { None };
+                                                    let name = if category.is_empty() { "Без категории" } else { category };
+                                                    let count = if shown.len() == *total { shown.len().to_string() } else { format!("{} из {}", shown.len(), total) };
+                                                    let header = egui::CollapsingHeader::new(format!("{} ({})", name, count)).id_source(("rule_category", name)).show(ui, |ui| {
+                                                        for (k, &i) in shown.iter().enumerate() {
+                                                            let rule = &self.rules[i];
+                                                            let selected = first_pos.is_some_and(|p| cursor_pos == Some(p + k));
+                                                            let button = ui.add_sized([ui.available_width(), 20.0], egui::Button::new(format!("{} - {}", rule.article, rule.title)).selected(selected));
+                                                            if cursor_moved && selected { button.scroll_to_me(None); }
+                                                            if button.clicked() { picked_rule = Some(rule.clone()); }
+                                                        }
+                                                    });
+                                                    if header.fully_open() { open_now.push(category.to_string()); }
+                                                }
+                                                self.open_rule_categories = open_now;
+                                            } else {
+                                                if hits.is_empty() { ui.label("Ничего не найдено"); }
+                                                let text_color = ui.visuals().text_color();
+                                                for (pos, hit) in hits.iter().enumerate() {
+                                                    let rule = &self.rules[hit.index];
+                                                    let job = search::highlight(&format!("{} - {}", rule.article, rule.title), &hit.terms, text_color, accent_color);
+                                                    let button = ui.add_sized([ui.available_width(), 20.0], egui::Button::new(job).selected(cursor_pos == Some(pos)));
+                                                    if cursor_moved && cursor_pos == Some(pos) { button.scroll_to_me(None); }
+                                                    if button.clicked() { picked_rule = Some(rule.clone()); }
+                                                    if let Some(snippet) = &hit.snippet {
+                                                        ui.label(search::highlight(snippet, &hit.terms, ui.visuals().weak_text_color(), accent_color));
+                                                    }
+                                                }
+                                            }
                                         });
-                                    } else { ui.label("Выберите правило слева"); }
+                                        if let Some(rule) = picked_rule { self.select_rule(rule); }
+                                    });
+                                    columns[1].vertical(|ui| {
+                                        let current_rule = self.selected_rule.clone();
+                                        if let Some(rule) = current_rule {
+                                            ui.horizontal(|ui| {
+                                                let favorite = self.config.favorite_rules.contains(&rule.article);
+                                                if ui.button(if favorite { "★" } else { "☆" }).on_hover_text(if favorite { "Убрать из избранного" } else { "В избранное" }).clicked() { self.toggle_favorite(&rule.article); }
+                                                ui.heading(format!("{} {}", rule.article, rule.title));
+                                            });
+                                            ui.separator();
+                                            let rule_pos = self.rules.iter().position(|r| r.category == rule.category && r.article == rule.article);
+                                            let mut jump_to: Option<usize> = None;
+                                            egui::ScrollArea::vertical().id_source("f7_desc_scroll").max_height(300.0).show(ui, |ui| {
+                                                let clean_desc = rule.description.replace("`n", "\n");
+                                                let parts = match rule_pos { Some(pos) => self.rule_refs.split(&self.rules, pos, &clean_desc), None => vec![DescPart::Text(clean_desc)] };
+                                                // Ссылки на другие статьи кликабельны; текст идет по строкам, чтобы переносы не терялись
+                                                ui.horizontal_wrapped(|ui| {
+                                                    ui.spacing_mut().item_spacing.x = 0.0;
+                                                    for part in &parts {
+                                                        match part {
+                                                            DescPart::Text(text) => for (n, line) in text.split('\n').enumerate() {
+                                                                if n > 0 { ui.end_row(); }
+                                                                if !line.is_empty() { ui.label(egui::RichText::new(line).italics()); }
+                                                            },
+                                                            DescPart::Link(text, target) => { if ui.link(egui::RichText::new(text).italics()).on_hover_text(&self.rules[*target].title).clicked() { jump_to = Some(*target); } },
+                                                        }
+                                                    }
+                                                });
+                                                let referenced_by = rule_pos.and_then(|pos| self.rule_refs.referenced_by.get(pos)).cloned().unwrap_or_default();
+                                                if !referenced_by.is_empty() {
+                                                    ui.add_space(5.0);
+                                                    ui.label(egui::RichText::new("Ссылаются на эту статью:").weak().size(12.0));
+                                                    ui.horizontal_wrapped(|ui| {
+                                                        for i in referenced_by { if ui.link(&self.rules[i].article).on_hover_text(&self.rules[i].title).clicked() { jump_to = Some(i); } }
+                                                    });
+                                                }
+                                            }); ui.separator();
+                                            if let Some(target) = jump_to { self.select_rule(self.rules[target].clone()); }
+                                            egui::Grid::new("punish_inputs").spacing([10.0, 10.0]).show(ui, |ui| {
+                                                ui.label("ID:");
+                                                let id_field = ui.add(egui::TextEdit::singleline(&mut self.input_id).desired_width(100.0));
+                                                if id_field.changed() { self.update_punish_command(); }
+                                                if std::mem::take(&mut self.focus_punish_id) { id_field.request_focus(); }
+                                                if id_field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) { self.issue_punishment(ctx); }
+                                                ui.end_row();
+                                                ui.label("Время:"); if ui.add(egui::TextEdit::singleline(&mut self.input_violation_time).desired_width(100.0)).changed() { self.update_punish_command(); } ui.end_row();
+                                                ui.label("ЖБ:"); if ui.add(egui::TextEdit::singleline(&mut self.input_report_num).desired_width(100.0)).changed() { self.update_punish_command(); } ui.end_row();
+                                                ui.label("Доказательства:"); if ui.add(egui::TextEdit::singleline(&mut self.input_evidence).desired_width(200.0).hint_text("ссылки через пробел")).changed() { self.update_punish_command(); } ui.end_row();
+                                                ui.label("Нарушений ранее:"); if ui.add(egui::DragValue::new(&mut self.input_prior_count).clamp_range(0..=20)).changed() { self.update_punish_command(); } ui.end_row();
+                                            });
+                                            for e in batch::parse_id_list(&self.input_id).unwrap_or_default().iter().flat_map(|id| journal::punished_today(&self.journal, id, &rule.article)) {
+                                                ui.label(egui::RichText::new(format!("⚠ ID {} уже наказан сегодня по {}: {} {}", e.player_id, e.article, e.time.split(' ').nth(1).unwrap_or(&e.time), e.punishment_text())).color(egui::Color32::from_rgb(255, 200, 0)));
+                                            }
+                                            if let Err(e) = reason::evidence_links(&self.input_evidence) { ui.label(egui::RichText::new(format!("❌ {}", e)).color(egui::Color32::RED)); }
+                                            ui.separator();
+                                            let options = Self::get_rule_options(&rule);
+                                            for (i, opt) in options.iter().enumerate() { if ui.radio_value(&mut self.selected_punishment_idx, i, &opt.label).changed() { self.input_custom_duration.clear(); self.update_punish_command(); } }
+                                            if let Some(opt) = options.get(self.selected_punishment_idx) {
+                                                let current = opt.ladder.tier_index(self.input_prior_count);
+                                                if opt.ladder.tiers.len() > 1 {
+                                                    // Клик по ступени = ручной выбор (число нарушений подстраивается под нее)
+                                                    ui.horizontal_wrapped(|ui| {
+                                                        ui.label("Ступени:");
+                                                        for (i, tier) in opt.ladder.tiers.iter().enumerate() {
+                                                            if ui.selectable_label(i == current, tier.to_string()).clicked() { self.input_prior_count = i as u32; self.input_custom_duration.clear(); self.update_punish_command(); }
+                                                        }
+                                                    });
+                                                }
+                                                if let Some(tier) = opt.ladder.tiers.get(current).filter(|t| t.is_range()) {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label(format!("Срок ({}):", tier));
+                                                        if ui.add(egui::TextEdit::singleline(&mut self.input_custom_duration).desired_width(80.0).hint_text(tier.min.to_string())).changed() { self.update_punish_command(); }
+                                                    });
+                                                }
+                                            }
+                                            if !self.punish_explanation.is_empty() { ui.label(egui::RichText::new(&self.punish_explanation).color(accent_color)); }
+                                            if !self.punish_warning.is_empty() { ui.label(egui::RichText::new(format!("⚠ {}", self.punish_warning)).color(egui::Color32::from_rgb(255, 200, 0))); }
+                                            if self.punish_long_bans.len() > 1 {
+                                                ui.horizontal_wrapped(|ui| {
+                                                    for cmd in self.punish_long_bans {
+                                                        let text = format!("{} ({})", cmd, punish::long_ban_hint(cmd));
+                                                        let allowed = data::command_level(cmd) <= self.config.admin_level;
+                                                        if ui.add_enabled(allowed, egui::SelectableLabel::new(self.selected_long_ban == *cmd, text)).on_disabled_hover_text(format!("Доступна с уровня {}", data::level_label(data::command_level(cmd)))).clicked() { self.selected_long_ban = cmd.to_string(); self.update_punish_command(); }
+                                                    }
+                                                });
+                                            }
+                                            ui.separator();
+                                            ui.add_sized([ui.available_width(), 30.0], egui::TextEdit::multiline(&mut self.generated_punish_cmd));
+                                            let too_long = if self.punish_cmd_valid { reason::too_long_lines(&self.generated_punish_cmd) } else { Vec::new() };
+                                            for (line, len) in &too_long { ui.label(egui::RichText::new(format!("❌ Строка {}: {} символов, в чат влезает {} - сократите причину или шаблон в настройках", line, len, reason::CHAT_LIMIT)).color(egui::Color32::RED)); }
+                                            let level = if self.punish_cmd_valid { data::check_level(&self.generated_punish_cmd, self.config.admin_level) } else { Ok(()) };
+                                            if let Err(e) = &level { ui.label(egui::RichText::new(format!("⛔ {} - наказание выдает старший администратор", e)).color(egui::Color32::RED)); }
+                                            if let Some(reversal) = self.punish_batch.first().filter(|_| self.punish_cmd_valid).and_then(|(cmd, _)| punish::reversal_for_command(cmd)) {
+                                                ui.label(egui::RichText::new(format!("↩ Отмена: {} ({}) - кнопка «Снять» в журнале", reversal.cmd_base, reversal.hint)).weak().size(12.0));
+                                            }
+                                            ui.horizontal(|ui| {
+                                                if ui.button("📋 Копировать").clicked() { if let Ok(mut clipboard) = Clipboard::new() { let _ = clipboard.set_text(self.generated_punish_cmd.clone()); } }
+                                                let send_label = if self.punish_batch.len() > 1 { format!("🚀 Выдать всем ({})", self.punish_batch.len()) } else { "🚀 Выдать (Enter)".to_string() };
+                                                let send = ui.add_enabled(self.punish_cmd_valid && too_long.is_empty() && level.is_ok() && !self.batch_queue.is_running(), egui::Button::new(send_label));
+                                                let send = if self.input_id.trim().is_empty() { send.on_disabled_hover_text("Сначала введите ID игрока") } else { send };
+                                                if send.clicked() { self.issue_punishment(ctx); }
+                                                let in_combo = self.combo_rules.iter().any(|p| p.rule.category == rule.category && p.rule.article == rule.article);
+                                                if ui.add_enabled(!options.is_empty() && !in_combo, egui::Button::new("➕ К нарушениям")).on_hover_text("Игрок нарушил несколько статей: собрать общее наказание").clicked() {
+                                                    // Ступень, свой срок и вид долгого бана запоминаются для каждой статьи отдельно
+                                                    self.combo_rules.push(punish::ComboPart { rule: rule.clone(), option: self.selected_punishment_idx, prior: self.input_prior_count, custom_duration: self.input_custom_duration.clone(), long_ban: self.selected_long_ban.clone() });
+                                                }
+                                            });
+                                            if self.punish_cmd_valid && !self.config.response_templates.is_empty() {
+                                                ui.horizontal(|ui| {
+                                                    ui.label("📨 Ответ на жалобу:");
+                                                    self.response_picker(ui);
+                                                    let response = self.response_text(&self.punish_batch, &rule.title);
+                                                    if ui.button("📋 Копировать ответ").on_hover_text(&response).clicked() { if let Ok(mut clipboard) = Clipboard::new() { let _ = clipboard.set_text(response); } }
+                                                });
+                                            }
+                                        } else { ui.label("Выберите правило слева"); }
+                                        if !self.combo_rules.is_empty() {
+                                            ui.separator();
+                                            ui.label(egui::RichText::new(format!("🧩 Несколько нарушений ({})", self.combo_rules.len())).strong());
+                                            let mut remove = None;
+                                            for (i, part) in self.combo_rules.iter_mut().enumerate() {
+                                                ui.horizontal(|ui| {
+                                                    if ui.small_button("✖").clicked() { remove = Some(i); }
+                                                    ui.label(&part.rule.article);
+                                                    let options = Self::get_rule_options(&part.rule);
+                                                    let current = options.get(part.option).map(|o| o.label.clone()).unwrap_or_default();
+                                                    egui::ComboBox::from_id_source(("combo_option", i)).selected_text(current).show_ui(ui, |ui| {
+                                                        // Свой срок и вид бана относились к прежнему варианту
+                                                        for (j, opt) in options.iter().enumerate() { if ui.selectable_value(&mut part.option, j, &opt.label).changed() { part.custom_duration.clear(); part.long_ban.clear(); } }
+                                                    });
+                                                    ui.label("ранее:");
+                                                    if ui.add(egui::DragValue::new(&mut part.prior).clamp_range(0..=20)).changed() { part.custom_duration.clear(); }
+                                                    if !part.custom_duration.is_empty() { ui.label(egui::RichText::new(format!("срок {}", part.custom_duration)).weak()); }
+                                                    if !part.long_ban.is_empty() { ui.label(egui::RichText::new(&part.long_ban).weak()); }
+                                                });
+                                            }
+                                            if let Some(i) = remove { self.combo_rules.remove(i); }
+                                            let (commands, combination) = self.combo_commands();
+                                            for note in &combination.notes { ui.label(egui::RichText::new(note).color(accent_color)); }
+                                            for e in &combination.errors { ui.label(egui::RichText::new(format!("⚠ {}", e)).color(egui::Color32::RED)); }
+                                            for (cmd, _) in &commands { ui.monospace(cmd); }
+                                            let text = commands.iter().map(|(c, _)| c.as_str()).collect::<Vec<_>>().join("\n");
+                                            let too_long = reason::too_long_lines(&text);
+                                            if !too_long.is_empty() { ui.label(egui::RichText::new(format!("❌ {} команд длиннее {} символов - не влезут в чат", too_long.len(), reason::CHAT_LIMIT)).color(egui::Color32::RED)); }
+                                            let level = data::check_level(&text, self.config.admin_level);
+                                            if let Err(e) = &level { ui.label(egui::RichText::new(format!("⛔ {}", e)).color(egui::Color32::RED)); }
+                                            ui.horizontal(|ui| {
+                                                if ui.button("📋 Копировать все").clicked() { if let Ok(mut clipboard) = Clipboard::new() { let _ = clipboard.set_text(text.clone()); } }
+                                                // Без ID команды собраны с "ID" только для предпросмотра
+                                                let has_ids = batch::parse_id_list(&self.input_id).is_ok_and(|ids| !ids.is_empty());
+                                                let send = ui.add_enabled(has_ids && !commands.is_empty() && combination.errors.is_empty() && too_long.is_empty() && level.is_ok() && !self.batch_queue.is_running(), egui::Button::new("🚀 Выдать все"));
+                                                let send = if has_ids { send } else { send.on_disabled_hover_text("Сначала введите ID игрока") };
+                                                if send.clicked() {
+                                                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+                                                    self.batch_queue.start(commands.clone());
+                                                    let articles: Vec<String> = self.combo_rules.iter().map(|p| p.rule.article.clone()).collect();
+                                                    self.remember_recent(&articles.iter().map(|a| a.as_str()).collect::<Vec<_>>());
+                                                }
+                                                if !self.config.response_templates.is_empty() {
+                                                    let titles = self.combo_rules.iter().map(|p| p.rule.title.as_str()).collect::<Vec<_>>().join(" + ");
+                                                    let response = self.response_text(&commands, &titles);
+                                                    if ui.button("📨 Ответ").on_hover_text(&response).clicked() { if let Ok(mut clipboard) = Clipboard::new() { let _ = clipboard.set_text(response); } }
+                                                }
+                                                if ui.button("Очистить").clicked() { self.combo_rules.clear(); }
+                                            });
+                                        }
+                                        let queue = self.batch_queue.items.lock().map(|items| items.clone()).unwrap_or_default();
+                                        if !queue.is_empty() {
+                                            ui.separator();
+                                            let sent = queue.iter().filter(|it| it.state == batch::BatchState::Sent).count();
+                                            ui.horizontal(|ui| {
+                                                ui.label(egui::RichText::new(format!("📤 Очередь: {} из {}", sent, queue.len())).strong());
+                                                if self.batch_queue.is_running() {
+                                                    if ui.button("⏹ Остановить").clicked() { self.batch_queue.cancel(); }
+                                                } else if ui.button("Очистить").clicked() { self.batch_queue.clear(); }
+                                            });
+                                            egui::ScrollArea::vertical().id_source("f7_queue_scroll").max_height(150.0).show(ui, |ui| {
+                                                for item in &queue { ui.monospace(format!("{} {}", item.state.icon(), item.command)); }
+                                            });
+                                        }
+                                    });
                                 });
-                            });
+                            }
                         },
                         MainTab::TeleportF8 => {
                             ui.vertical_centered(|ui| {
@@ -1681,7 +2723,9 @@ impl eframe::App for MyApp {
                                         egui::Grid::new("mp_c").striped(true).spacing([10.0, 10.0]).show(ui, |ui| {
                                             let presets = data::get_mp_commands(&self.config.admin_id);
                                             for (i, p) in presets.iter().enumerate() {
-                                                if ui.add_sized([250.0, 30.0], egui::Button::new(&p.button_name)).clicked() {
+                                                let preset_level = data::check_level(&p.commands.join("\n"), self.config.admin_level);
+                                                let button = ui.add_enabled_ui(preset_level.is_ok(), |ui| ui.add_sized([250.0, 30.0], egui::Button::new(&p.button_name))).inner;
+                                                if button.on_disabled_hover_text(preset_level.err().unwrap_or_default()).clicked() {
                                                     let cmds = p.commands.clone();
                                                     self.is_mp_running.store(true, Ordering::Relaxed);
                                                     let flag = self.is_mp_running.clone();
@@ -1692,7 +2736,7 @@ impl eframe::App for MyApp {
                                                          thread::sleep(Duration::from_millis(500));
                                                          log("MP Thread: Starting commands execution...");
                                                          for cmd in cmds {
-                                                             type_in_game(None, cmd, true, true, None);
+                                                             send_command(None, cmd, "МП");
                                                              thread::sleep(Duration::from_millis(1500));
                                                          }
                                                          flag.store(false, Ordering::Relaxed);
@@ -1708,7 +2752,8 @@ impl eframe::App for MyApp {
                                         egui::Grid::new("mp_tp_grid").striped(true).spacing([10.0, 10.0]).show(ui, |ui| {
                                             let teleports = data::get_mp_teleports();
                                             for (i, (name, coords)) in teleports.iter().enumerate() {
-                                                if ui.add_sized([250.0, 30.0], egui::Button::new(*name)).clicked() { run_teleport(ctx, *coords); } if (i + 1) % 2 == 0 { ui.end_row(); }
+                                                if ui.add_sized([250.0, 30.0], egui::Button::new(*name)).clicked() { run_teleport(ctx, coords); }
+                                                if (i + 1) % 2 == 0 { ui.end_row(); }
                                             }
                                         });
                                     });
@@ -1723,6 +2768,14 @@ impl eframe::App for MyApp {
 }
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--check-rules") {
+        attach_parent_console();
+        let path = args.get(pos + 1).filter(|a| !a.starts_with("--")).map(String::as_str).unwrap_or(rules::RULES_FILE);
+        let strict = args.iter().any(|a| a == "--strict");
+        std::process::exit(lint::run_check(path, strict));
+    }
+
     let mut viewport = egui::ViewportBuilder::default()
         .with_title("AdminHelper")
         .with_inner_size([750.0, 800.0])
@@ -0,0 +1,582 @@
+use crate::Rule;
+use std::fmt;
+
+// ================= СРОКИ =================
+// В rules.json встречаются только минуты ("120mi"), дни ("3d") и "Permban".
+// Всё остальное считаем опечаткой и ловим при загрузке правил.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PunishDuration {
+    Minutes(u32),
+    Days(u32),
+    Permanent,
+}
+
+impl PunishDuration {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let s = raw.trim();
+        if s.eq_ignore_ascii_case("permban") { return Ok(Self::Permanent); }
+
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (num, unit) = s.split_at(split);
+        if num.is_empty() { return Err(format!("'{}': нет числа", s)); }
+        let value: u32 = num.parse().map_err(|_| format!("'{}': слишком большое число", s))?;
+        if value == 0 { return Err(format!("'{}': нулевой срок", s)); }
+
+        match unit {
+            "mi" => Ok(Self::Minutes(value)),
+            "d" => Ok(Self::Days(value)),
+            "" => Err(format!("'{}': не указана единица (mi / d)", s)),
+            _ => Err(format!("'{}': неизвестная единица '{}'", s, unit)),
+        }
+    }
+
+    /// Длительность в минутах, для бессрочного - u64::MAX (удобно сравнивать).
+    pub fn total_minutes(&self) -> u64 {
+        match self {
+            Self::Minutes(m) => *m as u64,
+            Self::Days(d) => *d as u64 * 1440,
+            Self::Permanent => u64::MAX,
+        }
+    }
+
+    /// Срок в том виде, который принимает серверная команда.
+    /// Баны понимают и минуты, и дни; остальные команды со сроком - только минуты.
+    /// /perma срока не принимает вовсе.
+    pub fn command_arg(&self, cmd_base: &str) -> Result<String, String> {
+        match self {
+            Self::Permanent if cmd_base == "/perma" => Ok(String::new()),
+            Self::Permanent => Err(format!("{} не выдает бессрочные наказания", cmd_base)),
+            Self::Days(d) if matches!(cmd_base, "/ban" | "/sban" | "/strictban" | "/pban" | "/prolongedban") => Ok(format!("{}d", d)),
+            Self::Days(d) => Ok(format!("{}mi", *d as u64 * 1440)),
+            Self::Minutes(m) => Ok(format!("{}mi", m)),
+        }
+    }
+}
+
+impl fmt::Display for PunishDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Minutes(m) => write!(f, "{}mi", m),
+            Self::Days(d) => write!(f, "{}d", d),
+            Self::Permanent => write!(f, "Permban"),
+        }
+    }
+}
+
+/// Одна ступень: точный срок ("3d") или диапазон на выбор админа ("3d-7d", "2-7d").
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tier {
+    pub min: PunishDuration,
+    pub max: PunishDuration,
+}
+
+impl Tier {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let Some((low, high)) = raw.split_once('-') else {
+            let exact = PunishDuration::parse(raw)?;
+            return Ok(Self { min: exact, max: exact });
+        };
+
+        let max = PunishDuration::parse(high)?;
+        // "2-7d": у нижней границы единица берется от верхней
+        let low = low.trim();
+        let min = if !low.is_empty() && low.chars().all(|c| c.is_ascii_digit()) {
+            let unit = high.trim().trim_start_matches(|c: char| c.is_ascii_digit());
+            PunishDuration::parse(&format!("{}{}", low, unit))?
+        } else {
+            PunishDuration::parse(low)?
+        };
+
+        if min.total_minutes() > max.total_minutes() {
+            return Err(format!("'{}': нижняя граница больше верхней", raw.trim()));
+        }
+        Ok(Self { min, max })
+    }
+
+    pub fn is_range(&self) -> bool {
+        self.min != self.max
+    }
+
+    pub fn contains(&self, d: &PunishDuration) -> bool {
+        (self.min.total_minutes()..=self.max.total_minutes()).contains(&d.total_minutes())
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_range() { write!(f, "{}-{}", self.min, self.max) } else { write!(f, "{}", self.min) }
+    }
+}
+
+// ================= ЛЕСТНИЦА НАКАЗАНИЙ =================
+// В rules.json ступени записаны через "/" ("3d/7d/31d/Permban"):
+// первое нарушение - первая ступень, каждое повторное - следующая,
+// после последней ступени наказание больше не растет.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ladder {
+    pub tiers: Vec<Tier>,
+}
+
+impl Ladder {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let tiers = raw
+            .split('/')
+            .filter(|t| !t.trim().is_empty())
+            .map(Tier::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { tiers })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiers.is_empty()
+    }
+
+    /// Индекс ступени для игрока с `prior` нарушениями по этой статье.
+    pub fn tier_index(&self, prior: u32) -> usize {
+        (prior as usize).min(self.tiers.len().saturating_sub(1))
+    }
+}
+
+// ================= ВИДЫ НАКАЗАНИЙ =================
+// /ban на сервере работает по сроку: до суток включительно - деморган,
+// больше суток - блокировка аккаунта, но не дольше 30 дней.
+// Дольше - только /sban (новый бан) или /pban (продление), бессрочно - /perma.
+
+pub const DEMORGAN_LIMIT_MINUTES: u64 = 1440;
+pub const BAN_LIMIT_MINUTES: u64 = 30 * 1440;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PunishKind {
+    Demorgan,
+    Ban,
+    Warn,
+    VoiceMute,
+    ChatMute,
+    ReportMute,
+    Pacifist,
+    BanMarket,
+}
+
+impl PunishKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Demorgan => "Demorgan",
+            Self::Ban => "Ban",
+            Self::Warn => "Warn",
+            Self::VoiceMute => "Voice Mute",
+            Self::ChatMute => "Chat Mute",
+            Self::ReportMute => "Report Mute",
+            Self::Pacifist => "Pacifist",
+            Self::BanMarket => "Ban Market",
+        }
+    }
+
+    pub fn cmd_base(&self) -> &'static str {
+        match self {
+            Self::Demorgan | Self::Ban => "/ban",
+            Self::Warn => "/warn",
+            Self::VoiceMute => "/mutevoice",
+            Self::ChatMute => "/mutechat",
+            Self::ReportMute => "/mutereport",
+            Self::Pacifist => "/pacifist",
+            Self::BanMarket => "/ban_market_content_creation",
+        }
+    }
+}
+
+/// Чем выдавать срок, который не помещается в /ban (первый вариант - по умолчанию).
+pub fn long_ban_commands(d: &PunishDuration) -> &'static [&'static str] {
+    match d {
+        PunishDuration::Permanent => &["/perma"],
+        _ if d.total_minutes() > BAN_LIMIT_MINUTES => &["/sban", "/pban"],
+        _ => &[],
+    }
+}
+
+pub fn long_ban_hint(cmd: &str) -> &'static str {
+    match cmd {
+        "/sban" => "новый бан более 30 дней",
+        "/pban" => "продлить текущий бан",
+        "/perma" => "бессрочно, на всех серверах",
+        _ => "",
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct PunishmentOption {
+    pub kind: PunishKind,
+    pub label: String,
+    pub cmd_base: String,
+    pub ladder: Ladder,
+    pub error: Option<String>,
+}
+
+pub struct TierChoice {
+    pub cmd_base: String,
+    pub time_arg: String,
+    /// Выбранный срок (нет у наказаний без срока и при ошибке)
+    pub duration: Option<PunishDuration>,
+    pub explanation: String,
+    pub warning: Option<String>,
+    pub alternatives: &'static [&'static str],
+    pub error: Option<String>,
+}
+
+impl TierChoice {
+    fn failed(cmd_base: &str, explanation: String, error: String) -> Self {
+        Self { cmd_base: cmd_base.to_string(), time_arg: String::new(), duration: None, explanation, warning: None, alternatives: &[], error: Some(error) }
+    }
+}
+
+impl PunishmentOption {
+    fn new(kind: PunishKind, raw: &str) -> Self {
+        let (ladder, error) = match Ladder::parse(raw) {
+            Ok(ladder) => (ladder, None),
+            Err(e) => (Ladder { tiers: Vec::new() }, Some(e)),
+        };
+        let label = if raw.is_empty() { kind.label().to_string() } else { format!("{} {}", kind.label(), raw) };
+        Self { kind, label, cmd_base: kind.cmd_base().to_string(), ladder, error }
+    }
+
+    /// `custom` - срок, введенный админом для ступени-диапазона (пусто = нижняя граница).
+    /// `long_ban` - выбранная админом замена /ban для сроков больше 30 дней (пусто = по умолчанию).
+    pub fn choose(&self, prior: u32, custom: &str, long_ban: &str) -> TierChoice {
+        if let Some(e) = &self.error {
+            return TierChoice::failed(&self.cmd_base, String::new(), format!("Ошибка в rules.json: {}", e));
+        }
+        if self.ladder.is_empty() {
+            return TierChoice { cmd_base: self.cmd_base.clone(), time_arg: String::new(), duration: None, explanation: "Наказание выдается без срока.".to_string(), warning: None, alternatives: &[], error: None };
+        }
+
+        let index = self.ladder.tier_index(prior);
+        let tier = self.ladder.tiers[index];
+        let total = self.ladder.tiers.len();
+
+        let mut explanation = if total == 1 {
+            format!("Единственная ступень: {}", tier)
+        } else if prior == 0 {
+            format!("Первичное нарушение → ступень 1 из {}: {}", total, tier)
+        } else if prior as usize >= total {
+            format!("Нарушений ранее: {} → ступень {} из {} (максимальная): {}", prior, index + 1, total, tier)
+        } else {
+            format!("Нарушений ранее: {} → ступень {} из {}: {}", prior, index + 1, total, tier)
+        };
+
+        let duration = if custom.trim().is_empty() {
+            tier.min
+        } else {
+            match PunishDuration::parse(custom) {
+                Ok(d) if tier.contains(&d) => d,
+                Ok(d) => return TierChoice::failed(&self.cmd_base, explanation, format!("Срок {} вне диапазона {}", d, tier)),
+                Err(e) => return TierChoice::failed(&self.cmd_base, explanation, format!("Неверный срок {}", e)),
+            }
+        };
+        if tier.is_range() { explanation = format!("{}; срок: {}", explanation, duration); }
+
+        let mut cmd_base = self.cmd_base.clone();
+        let mut warning = None;
+        let mut alternatives: &'static [&'static str] = &[];
+        if cmd_base == "/ban" {
+            alternatives = long_ban_commands(&duration);
+            if let Some(default) = alternatives.first() {
+                cmd_base = if alternatives.contains(&long_ban) { long_ban.to_string() } else { default.to_string() };
+                warning = Some(format!("Срок {} больше, чем позволяет /ban (30 дней) → {}", duration, cmd_base));
+            } else if self.kind == PunishKind::Demorgan && duration.total_minutes() > DEMORGAN_LIMIT_MINUTES {
+                warning = Some(format!("Срок {} больше суток: /ban заблокирует аккаунт, а не отправит в деморган", duration));
+            } else if self.kind == PunishKind::Ban && duration.total_minutes() <= DEMORGAN_LIMIT_MINUTES {
+                warning = Some(format!("Срок {} до суток: /ban отправит в деморган, а не заблокирует аккаунт", duration));
+            }
+        }
+
+        match duration.command_arg(&cmd_base) {
+            Ok(time_arg) => TierChoice { cmd_base, time_arg, duration: Some(duration), explanation, warning, alternatives, error: None },
+            Err(e) => TierChoice::failed(&cmd_base, explanation, e),
+        }
+    }
+}
+
+/// Поля правила со сроками в исходном порядке вывода.
+fn duration_fields(rule: &Rule) -> [(PunishKind, &str); 7] {
+    [
+        (PunishKind::Demorgan, &rule.demorgan),
+        (PunishKind::Ban, &rule.ban),
+        (PunishKind::VoiceMute, &rule.mutev),
+        (PunishKind::ChatMute, &rule.mute_chat),
+        (PunishKind::ReportMute, &rule.mute_report),
+        (PunishKind::Pacifist, &rule.pacifist),
+        (PunishKind::BanMarket, &rule.ban_market),
+    ]
+}
+
+pub fn rule_options(rule: &Rule) -> Vec<PunishmentOption> {
+    let fields = duration_fields(rule);
+    // Порядок как раньше: деморган и бан, затем варн, затем остальное
+    let (bans, rest) = fields.split_at(2);
+    let mut options: Vec<PunishmentOption> = bans.iter().filter(|f| !f.1.is_empty()).map(|(kind, raw)| PunishmentOption::new(*kind, raw)).collect();
+    if rule.warn > 0 { options.push(PunishmentOption::new(PunishKind::Warn, "")); }
+    options.extend(rest.iter().filter(|f| !f.1.is_empty()).map(|(kind, raw)| PunishmentOption::new(*kind, raw)));
+    options
+}
+
+/// Есть ли у статьи хоть какое-то наказание (часть статей - просто пояснения).
+pub fn has_punishment(rule: &Rule) -> bool {
+    rule.warn > 0 || duration_fields(rule).iter().any(|(_, raw)| !raw.trim().is_empty())
+}
+
+/// Проверка всех сроков правил при загрузке: одна строка на каждое битое поле.
+pub fn validate_rules(rules: &[Rule]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for rule in rules {
+        for (kind, raw) in duration_fields(rule) {
+            if let Err(e) = Ladder::parse(raw) {
+                errors.push(format!("{} ({}): {}", rule.article, kind.label(), e));
+            }
+        }
+    }
+    errors
+}
+
+// ================= НЕСКОЛЬКО НАРУШЕНИЙ =================
+// Деморган и бан - одно и то же /ban, поэтому из них выдается один, самый долгий.
+// Муты, пацифист и бан маркета - по одному на вид, тоже самый долгий.
+// Варны складываются: по варну за каждую статью.
+
+pub struct CombinedCommand {
+    pub kind: PunishKind,
+    pub cmd_base: String,
+    pub time_arg: String,
+    /// Статьи, которые покрывает команда (для причины)
+    pub articles: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct Combination {
+    pub commands: Vec<CombinedCommand>,
+    /// Что было поглощено более строгим наказанием
+    pub notes: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Статья в комбинации и то, что для нее выбрано в F7 на момент добавления:
+/// вариант наказания (индекс в `rule_options`), число прошлых нарушений,
+/// свой срок внутри диапазона и вид долгого бана (/sban, /pban).
+#[derive(Clone)]
+pub struct ComboPart {
+    pub rule: Rule,
+    pub option: usize,
+    pub prior: u32,
+    pub custom_duration: String,
+    pub long_ban: String,
+}
+
+/// Команда + ее срок в минутах (0 - без срока) + что выбрано по каждой статье
+struct ComboGroup {
+    command: CombinedCommand,
+    minutes: u64,
+    picks: Vec<(String, String)>,
+}
+
+pub fn combine(parts: &[ComboPart]) -> Combination {
+    let mut result = Combination::default();
+    let mut groups: Vec<ComboGroup> = Vec::new();
+
+    for part in parts {
+        let rule = &part.rule;
+        let options = rule_options(rule);
+        let Some(option) = options.get(part.option) else {
+            result.errors.push(format!("{}: нет наказания", rule.article));
+            continue;
+        };
+        let choice = option.choose(part.prior, &part.custom_duration, &part.long_ban);
+        if let Some(e) = choice.error {
+            result.errors.push(format!("{} ({}): {}", rule.article, option.label, e));
+            continue;
+        }
+        let minutes = choice.duration.map(|d| d.total_minutes()).unwrap_or(0);
+        let chosen = (rule.article.clone(), if choice.time_arg.is_empty() { option.label.clone() } else { format!("{} {}", option.kind.label(), choice.time_arg) });
+        // Деморган и бан объединяются в одну группу
+        let group_kind = if option.kind == PunishKind::Demorgan { PunishKind::Ban } else { option.kind };
+        let existing = if group_kind == PunishKind::Warn { None } else { groups.iter_mut().find(|g| g.command.kind == group_kind) };
+        match existing {
+            Some(group) => {
+                group.command.articles.push(rule.article.clone());
+                group.picks.push(chosen);
+                if minutes > group.minutes {
+                    group.minutes = minutes;
+                    group.command.cmd_base = choice.cmd_base;
+                    group.command.time_arg = choice.time_arg;
+                }
+            }
+            None => groups.push(ComboGroup { command: CombinedCommand { kind: group_kind, cmd_base: choice.cmd_base, time_arg: choice.time_arg, articles: vec![rule.article.clone()] }, minutes, picks: vec![chosen] }),
+        }
+    }
+
+    for ComboGroup { command: cmd, picks, .. } in groups {
+        if picks.len() > 1 {
+            let absorbed: Vec<String> = picks.iter().map(|(article, text)| format!("{} ({})", article, text)).collect();
+            let term = if cmd.time_arg.is_empty() { String::new() } else { format!(" {}", cmd.time_arg) };
+            result.notes.push(format!("{}{} покрывает: {}", cmd.cmd_base, term, absorbed.join(", ")));
+        }
+        result.commands.push(cmd);
+    }
+    // Сначала бан, потом варны, потом остальное - как в списке вариантов F7
+    result.commands.sort_by_key(|c| c.kind as u8);
+    result
+}
+
+// ================= СНЯТИЕ НАКАЗАНИЙ =================
+
+#[derive(Clone, Copy)]
+pub struct Reversal {
+    pub cmd_base: &'static str,
+    /// /unmute принимает только ID, остальные - ID и причину
+    pub takes_reason: bool,
+    pub hint: &'static str,
+}
+
+impl PunishKind {
+    pub fn reversal(&self) -> Option<Reversal> {
+        match self {
+            Self::Demorgan | Self::Ban => Some(Reversal { cmd_base: "/unban", takes_reason: true, hint: "снимает бан и деморган" }),
+            Self::Warn => Some(Reversal { cmd_base: "/unwarn", takes_reason: true, hint: "снимает последнее предупреждение" }),
+            Self::VoiceMute | Self::ChatMute | Self::ReportMute => Some(Reversal { cmd_base: "/unmute", takes_reason: false, hint: "снимает все муты сразу" }),
+            Self::Pacifist => Some(Reversal { cmd_base: "/unpacifist", takes_reason: true, hint: "снимает режим пацифиста" }),
+            Self::BanMarket => None,
+        }
+    }
+}
+
+/// Обратная команда по уже набранной (понимает и короткие формы /mv, /sban...).
+pub fn reversal_for_command(command: &str) -> Option<Reversal> {
+    let kind = match command.split_whitespace().next()? {
+        "/ban" | "/sban" | "/strictban" | "/pban" | "/prolongedban" | "/perma" => PunishKind::Ban,
+        "/warn" => PunishKind::Warn,
+        "/mutevoice" | "/mv" => PunishKind::VoiceMute,
+        "/mutechat" | "/mc" => PunishKind::ChatMute,
+        "/mutereport" | "/mr" => PunishKind::ReportMute,
+        "/pacifist" => PunishKind::Pacifist,
+        _ => return None,
+    };
+    kind.reversal()
+}
+
+impl Reversal {
+    pub fn command(&self, id: &str, reason: &str) -> String {
+        if self.takes_reason && !reason.trim().is_empty() { format!("{} {} {}", self.cmd_base, id, reason.trim()) } else { format!("{} {}", self.cmd_base, id) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(article: &str, ban: &str, mute_chat: &str) -> Rule {
+        Rule { ban: ban.into(), mute_chat: mute_chat.into(), ..Rule::for_test("ПП", article, "") }
+    }
+
+    fn part(rule: Rule, option: usize, prior: u32, custom: &str, long_ban: &str) -> ComboPart {
+        ComboPart { rule, option, prior, custom_duration: custom.into(), long_ban: long_ban.into() }
+    }
+
+    fn commands(c: &Combination) -> Vec<String> {
+        c.commands.iter().map(|c| format!("{} {}", c.cmd_base, c.time_arg).trim().to_string()).collect()
+    }
+
+    #[test]
+    fn duration_units_and_permban() {
+        assert_eq!(PunishDuration::parse("120mi"), Ok(PunishDuration::Minutes(120)));
+        assert_eq!(PunishDuration::parse(" 3d "), Ok(PunishDuration::Days(3)));
+        assert_eq!(PunishDuration::parse("PermBan"), Ok(PunishDuration::Permanent));
+        assert_eq!(PunishDuration::Days(2).total_minutes(), 2880);
+        assert_eq!(PunishDuration::parse("31d").unwrap().to_string(), "31d");
+    }
+
+    #[test]
+    fn duration_typos_are_errors() {
+        // Единицы пишутся строго как в rules.json: "3D" и "3 d" - опечатки
+        for raw in ["3D", "3 d", "3", "d", "0d", "3h", "", "99999999999mi"] {
+            assert!(PunishDuration::parse(raw).is_err(), "{:?} должно быть ошибкой", raw);
+        }
+    }
+
+    #[test]
+    fn duration_command_arg_per_command() {
+        assert_eq!(PunishDuration::Days(3).command_arg("/ban"), Ok("3d".to_string()));
+        assert_eq!(PunishDuration::Days(1).command_arg("/mutechat"), Ok("1440mi".to_string()));
+        assert_eq!(PunishDuration::Permanent.command_arg("/perma"), Ok(String::new()));
+        assert!(PunishDuration::Permanent.command_arg("/ban").is_err());
+    }
+
+    #[test]
+    fn tier_exact_and_ranges() {
+        let exact = Tier::parse("3d").unwrap();
+        assert!(!exact.is_range());
+        let range = Tier::parse("3d-7d").unwrap();
+        assert_eq!(range, Tier::parse("3-7d").unwrap());
+        assert_eq!(range.to_string(), "3d-7d");
+        assert!(range.contains(&PunishDuration::Days(5)));
+        assert!(range.contains(&PunishDuration::Minutes(3 * 1440)));
+        assert!(!range.contains(&PunishDuration::Days(8)));
+        assert_eq!(Tier::parse("60mi-2d").unwrap().min, PunishDuration::Minutes(60));
+        assert!(Tier::parse("7d-3d").is_err());
+        assert!(Tier::parse("3d-").is_err());
+        assert!(Tier::parse("3D-7d").is_err());
+    }
+
+    #[test]
+    fn ladder_steps_up_and_stops_at_last_tier() {
+        let ladder = Ladder::parse("3d/7d/31d/Permban").unwrap();
+        assert_eq!(ladder.tiers.len(), 4);
+        assert_eq!(ladder.tier_index(0), 0);
+        assert_eq!(ladder.tier_index(2), 2);
+        assert_eq!(ladder.tier_index(10), 3);
+        assert_eq!(ladder.tiers[ladder.tier_index(10)].min, PunishDuration::Permanent);
+    }
+
+    #[test]
+    fn ladder_skips_empty_steps_and_rejects_bad_ones() {
+        assert!(Ladder::parse("").unwrap().is_empty());
+        assert_eq!(Ladder::parse("").unwrap().tier_index(3), 0);
+        assert_eq!(Ladder::parse(" 3d / /7d ").unwrap().tiers.len(), 2);
+        assert!(Ladder::parse("3d/7x").is_err());
+    }
+
+    #[test]
+    fn reversal_commands_for_ban_mute_and_warn() {
+        let command = |issued: &str| reversal_for_command(issued).map(|r| r.command("12", " Ошибочная выдача (3.2 ПП) "));
+        assert_eq!(command("/ban 12 3d 3.2 ПП").as_deref(), Some("/unban 12 Ошибочная выдача (3.2 ПП)"));
+        assert_eq!(command("/sban 12 60d 3.2 ПП").as_deref(), Some("/unban 12 Ошибочная выдача (3.2 ПП)"));
+        assert_eq!(command("/warn 12 3.2 ПП").as_deref(), Some("/unwarn 12 Ошибочная выдача (3.2 ПП)"));
+        // /unmute причину не принимает
+        assert_eq!(command("/mc 12 30mi 3.2 ПП").as_deref(), Some("/unmute 12"));
+        assert_eq!(command("/mutevoice 12 30mi 3.2 ПП").as_deref(), Some("/unmute 12"));
+        assert_eq!(reversal_for_command("/ban 12 3d").unwrap().command("12", " "), "/unban 12");
+        assert!(reversal_for_command("/kick 12").is_none());
+        assert!(reversal_for_command("").is_none());
+        assert!(PunishKind::BanMarket.reversal().is_none());
+    }
+
+    #[test]
+    fn combine_uses_each_article_ladder() {
+        let parts = [part(rule("1.1", "3d/7d/31d", ""), 0, 0, "", ""), part(rule("1.2", "", "30mi/60mi"), 0, 1, "", "")];
+        let c = combine(&parts);
+        assert!(c.errors.is_empty());
+        assert_eq!(commands(&c), ["/ban 3d", "/mutechat 60mi"]);
+    }
+
+    #[test]
+    fn combine_keeps_custom_duration_and_long_ban() {
+        let parts = [part(rule("1.1", "3d/7d/60d", ""), 0, 2, "", "/pban"), part(rule("1.2", "3d-7d", ""), 0, 0, "5d", "")];
+        let c = combine(&parts);
+        assert_eq!(commands(&c), ["/pban 60d"]);
+        assert_eq!(c.commands[0].articles, ["1.1", "1.2"]);
+
+        let parts = [part(rule("1.1", "3d/7d", ""), 0, 0, "", ""), part(rule("1.2", "3d-7d", ""), 0, 0, "5d", "")];
+        assert_eq!(commands(&combine(&parts)), ["/ban 5d"]);
+        // Свой срок вне диапазона ступени - ошибка, а не молчаливая замена
+        let parts = [part(rule("1.2", "3d-7d", ""), 0, 0, "9d", "")];
+        assert_eq!(combine(&parts).errors.len(), 1);
+    }
+}
@@ -0,0 +1,220 @@
+use chrono::Local;
+
+// ================= ШАБЛОН ПРИЧИНЫ =================
+// Причина наказания и ответ на форум собираются по шаблонам из config.json.
+// {имя} - подстановка, [ ... ] - необязательный кусок: выводится, только если
+// все подстановки внутри него заполнены. Например "[ | {report}]" исчезает без номера жалобы.
+
+pub const DEFAULT_TEMPLATE: &str = "{article}[ (Ранее {time})][ | {report}]";
+/// Длина строки ввода чата в игре: все, что длиннее, обрезается вместе с причиной.
+pub const CHAT_LIMIT: usize = 128;
+
+pub const PLACEHOLDERS: [(&str, &str); 7] = [
+    ("article", "номер статьи (3.2 ПП)"),
+    ("title", "название статьи"),
+    ("time", "время нарушения из поля «Время»"),
+    ("report", "номер жалобы (№1234)"),
+    ("admin", "ваш ник"),
+    ("date", "сегодняшняя дата"),
+    ("evidence", "ссылки на доказательства"),
+];
+/// В ответе на форум доступно еще и то, что известно только после выбора наказания.
+pub const RESPONSE_PLACEHOLDERS: [(&str, &str); 4] = [
+    ("id", "ID игрока"),
+    ("punishment", "наказание со сроком (Ban 3d)"),
+    ("duration", "срок (3d)"),
+    ("command", "команда целиком"),
+];
+
+#[derive(Default)]
+pub struct ReasonFields {
+    pub article: String,
+    pub title: String,
+    pub time: String,
+    pub report: String,
+    pub admin: String,
+    pub date: String,
+    pub evidence: String,
+    pub id: String,
+    pub punishment: String,
+    pub duration: String,
+    pub command: String,
+}
+
+impl ReasonFields {
+    fn get(&self, name: &str) -> Option<&str> {
+        match name {
+            "article" => Some(&self.article),
+            "title" => Some(&self.title),
+            "time" => Some(&self.time),
+            "report" => Some(&self.report),
+            "admin" => Some(&self.admin),
+            "date" => Some(&self.date),
+            "evidence" => Some(&self.evidence),
+            "id" => Some(&self.id),
+            "punishment" => Some(&self.punishment),
+            "duration" => Some(&self.duration),
+            "command" => Some(&self.command),
+            _ => None,
+        }
+    }
+
+    /// Пример для предпросмотра в настройках.
+    pub fn sample(admin: &str) -> Self {
+        Self {
+            article: "3.2 ПП".to_string(),
+            title: "Оскорбление родных".to_string(),
+            time: "12:30".to_string(),
+            report: "№1234".to_string(),
+            admin: admin.to_string(),
+            date: Local::now().format("%d.%m.%Y").to_string(),
+            evidence: "https://imgur.com/a/abc123".to_string(),
+            id: "12156".to_string(),
+            punishment: "Ban 3d".to_string(),
+            duration: "3d".to_string(),
+            command: "/ban 12156 3d 3.2 ПП | №1234".to_string(),
+        }
+    }
+}
+
+enum Piece {
+    Text(String),
+    Field(String),
+    Optional(Vec<Piece>),
+}
+
+fn known(name: &str, response: bool) -> bool {
+    PLACEHOLDERS.iter().any(|(p, _)| *p == name) || (response && RESPONSE_PLACEHOLDERS.iter().any(|(p, _)| *p == name))
+}
+
+fn parse(template: &str, response: bool) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut optional: Option<Vec<Piece>> = None;
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        let piece = match c {
+            '{' => {
+                let rest = chars.as_str();
+                let Some(end) = rest.find('}') else { return Err("не закрыта '{'".to_string()) };
+                let name = rest[..end].to_string();
+                chars = rest[end + 1..].chars();
+                if !known(&name, response) { return Err(format!("неизвестная подстановка {{{}}}", name)); }
+                Piece::Field(name)
+            }
+            '}' => return Err("лишняя '}'".to_string()),
+            '[' if optional.is_some() => return Err("[ ] не могут быть вложенными".to_string()),
+            '[' => { optional = Some(Vec::new()); continue; }
+            ']' => match optional.take() {
+                Some(inner) => Piece::Optional(inner),
+                None => return Err("лишняя ']'".to_string()),
+            },
+            c => Piece::Text(c.to_string()),
+        };
+        match &mut optional {
+            Some(inner) if !matches!(piece, Piece::Optional(_)) => inner.push(piece),
+            _ => pieces.push(piece),
+        }
+    }
+    if optional.is_some() { return Err("не закрыта '['".to_string()); }
+    Ok(pieces)
+}
+
+pub fn validate(template: &str) -> Result<(), String> {
+    let pieces = parse(template, false)?;
+    if !pieces.iter().any(|p| matches!(p, Piece::Field(f) if f == "article")) { return Err("нет {article} вне [ ]".to_string()); }
+    Ok(())
+}
+
+pub fn validate_response(template: &str) -> Result<(), String> {
+    parse(template, true).map(|_| ())
+}
+
+fn render_pieces(pieces: &[Piece], fields: &ReasonFields, out: &mut String) {
+    for piece in pieces {
+        match piece {
+            Piece::Text(t) => out.push_str(t),
+            Piece::Field(name) => out.push_str(fields.get(name).unwrap_or_default()),
+            Piece::Optional(inner) => {
+                let filled = inner.iter().all(|p| match p { Piece::Field(name) => !fields.get(name).unwrap_or_default().trim().is_empty(), _ => true });
+                if filled { render_pieces(inner, fields, out); }
+            }
+        }
+    }
+}
+
+/// Битый шаблон заменяется стандартным, чтобы F7 продолжал работать.
+pub fn render(template: &str, fields: &ReasonFields) -> String {
+    let template = if validate(template).is_ok() { template } else { DEFAULT_TEMPLATE };
+    let mut out = String::new();
+    render_pieces(&parse(template, false).unwrap_or_default(), fields, &mut out);
+    out.trim().to_string()
+}
+
+/// Ответ на жалобу; битый шаблон выводится как есть, чтобы ошибку было видно.
+pub fn render_response(template: &str, fields: &ReasonFields) -> String {
+    match parse(template, true) {
+        Ok(pieces) => {
+            let mut out = String::new();
+            render_pieces(&pieces, fields, &mut out);
+            out.trim().to_string()
+        }
+        Err(_) => template.to_string(),
+    }
+}
+
+/// Ссылки на доказательства через пробел, запятую или с новой строки.
+pub fn evidence_links(input: &str) -> Result<Vec<String>, String> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|l| !l.is_empty())
+        .map(|l| if l.starts_with("http://") || l.starts_with("https://") { Ok(l.to_string()) } else { Err(format!("'{}' не похоже на ссылку (нужно http:// или https://)", l)) })
+        .collect()
+}
+
+/// Строки, которые не влезут в чат: (номер строки с 1, длина).
+pub fn too_long_lines(text: &str) -> Vec<(usize, usize)> {
+    text.lines().enumerate().map(|(i, l)| (i + 1, l.trim().chars().count())).filter(|(_, len)| *len > CHAT_LIMIT).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(article: &str, time: &str, report: &str) -> ReasonFields {
+        ReasonFields { article: article.into(), time: time.into(), report: report.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn optional_sections_need_all_their_fields() {
+        assert_eq!(render(DEFAULT_TEMPLATE, &fields("3.2 ПП", "12:30", "№1234")), "3.2 ПП (Ранее 12:30) | №1234");
+        assert_eq!(render(DEFAULT_TEMPLATE, &fields("3.2 ПП", "", "№1234")), "3.2 ПП | №1234");
+        assert_eq!(render(DEFAULT_TEMPLATE, &fields("3.2 ПП", "  ", "")), "3.2 ПП");
+        assert_eq!(render("{article}[ {time} {report}]", &fields("1.1", "12:30", "")), "1.1");
+    }
+
+    #[test]
+    fn broken_reason_template_falls_back_to_default() {
+        for bad in ["{article", "{article}}", "[{article}", "{article}]", "[[{time}]]", "{nope} {article}", "[{article}]"] {
+            assert!(validate(bad).is_err(), "{:?}", bad);
+            assert_eq!(render(bad, &fields("1.1", "", "№7")), "1.1 | №7");
+        }
+    }
+
+    #[test]
+    fn response_template_knows_response_fields() {
+        let f = ReasonFields { id: "12".into(), punishment: "Ban 3d".into(), ..fields("1.1", "", "") };
+        let template = "{id}: {punishment}[ ({report})]";
+        assert!(validate(template).is_err());
+        assert!(validate_response(template).is_ok());
+        assert_eq!(render_response(template, &f), "12: Ban 3d");
+        assert_eq!(render_response("{id", &f), "{id");
+    }
+
+    #[test]
+    fn evidence_and_chat_limit() {
+        assert_eq!(evidence_links("https://a.b, http://c.d\nhttps://e.f").unwrap().len(), 3);
+        assert!(evidence_links("imgur.com/abc").is_err());
+        let long = "я".repeat(CHAT_LIMIT + 1);
+        assert_eq!(too_long_lines(&format!("ok\n{}", long)), [(2, CHAT_LIMIT + 1)]);
+    }
+}
@@ -0,0 +1,525 @@
+use crate::{log, Rule};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// ================= ФАЙЛ ПРАВИЛ =================
+// Правила читаются из rules.json рядом с config.json, чтобы их можно было
+// обновлять без нового релиза. Встроенная копия - только запасной вариант.
+
+pub const RULES_FILE: &str = "rules.json";
+const EMBEDDED_RULES: &str = include_str!("../rules.json");
+// Копия последнего принятого пакета и пакета до него - для просмотра изменений
+const INSTALLED_FILE: &str = "rules.installed.json";
+const PREVIOUS_FILE: &str = "rules.prev.json";
+// rules.json, замененный более новой встроенной редакцией
+const OLD_FILE: &str = "rules.old.json";
+
+/// Пакет правил: версия и дата редакции регламента + сами правила.
+/// Старый формат (просто массив правил) тоже читается, но без версии.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RulesPack {
+    #[serde(default)] pub version: String,
+    #[serde(default)] pub date: String,
+    pub rules: Vec<Rule>,
+}
+
+impl RulesPack {
+    pub fn title(&self) -> String {
+        match (self.version.is_empty(), self.date.is_empty()) {
+            (true, _) => "без версии".to_string(),
+            (false, true) => format!("v{}", self.version),
+            (false, false) => format!("v{} от {}", self.version, self.date),
+        }
+    }
+
+    fn same_release(&self, other: &RulesPack) -> bool {
+        self.version == other.version && self.date == other.date
+    }
+
+    /// Редакция новее `other`: версии сравниваются по числам ("1.10" > "1.9"),
+    /// при равных - по дате (ГГГГ-ММ-ДД). Пакет без версии старше любого.
+    pub fn is_newer_than(&self, other: &RulesPack) -> bool {
+        let numbers = |v: &str| v.split('.').map(|n| n.trim().parse::<u32>().unwrap_or(0)).collect::<Vec<_>>();
+        let key = |p: &RulesPack| (!p.version.is_empty(), numbers(&p.version), p.date.clone());
+        key(self) > key(other)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RulesError {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl RulesError {
+    pub fn describe(&self) -> String {
+        let path = if self.path.is_empty() || self.path == "." { "корень".to_string() } else { self.path.clone() };
+        format!("{}: {} (путь: {}, строка {}, столбец {})", RULES_FILE, self.message, path, self.line, self.column)
+    }
+}
+
+fn deserialize_path<'de, T: Deserialize<'de>>(json: &'de str) -> Result<T, RulesError> {
+    let de = &mut serde_json::Deserializer::from_str(json);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        let path = e.path().to_string();
+        let inner = e.into_inner();
+        RulesError { path, line: inner.line(), column: inner.column(), message: inner.to_string() }
+    })
+}
+
+pub fn parse_rules(json: &str) -> Result<RulesPack, RulesError> {
+    if json.trim_start().starts_with('[') {
+        return deserialize_path(json).map(|rules| RulesPack { rules, ..Default::default() });
+    }
+    deserialize_path(json)
+}
+
+fn embedded_rules() -> RulesPack {
+    parse_rules(EMBEDDED_RULES).unwrap_or_default()
+}
+
+/// Правила с диска; при первом запуске файл создается из встроенной копии.
+/// Если файл битый - встроенная копия и ошибка для панели в F7.
+/// Если в программу встроена более новая редакция, она заменяет файл на диске
+/// (старый сохраняется в rules.old.json) - третье значение говорит об этом.
+pub fn load_rules() -> (RulesPack, Option<RulesError>, Option<String>) {
+    match fs::read_to_string(RULES_FILE) {
+        Ok(json) => match parse_rules(&json) {
+            Ok(rules) => {
+                let embedded = embedded_rules();
+                if !embedded.is_newer_than(&rules) { return (rules, None, None); }
+                log(&format!("Rules: embedded pack {} is newer than {} {}, replacing", embedded.title(), RULES_FILE, rules.title()));
+                let _ = fs::write(OLD_FILE, &json);
+                let _ = fs::write(RULES_FILE, EMBEDDED_RULES);
+                let notice = format!("Регламент обновлен до {} вместе с программой. Прежний {} ({}) сохранен как {}.", embedded.title(), RULES_FILE, rules.title(), OLD_FILE);
+                (embedded, None, Some(notice))
+            }
+            Err(e) => {
+                log(&format!("Rules: {}", e.describe()));
+                (embedded_rules(), Some(e), None)
+            }
+        },
+        Err(_) => {
+            let _ = fs::write(RULES_FILE, EMBEDDED_RULES);
+            (embedded_rules(), None, None)
+        }
+    }
+}
+
+fn modified_time() -> Option<SystemTime> {
+    fs::metadata(RULES_FILE).and_then(|m| m.modified()).ok()
+}
+
+/// Следит за rules.json и присылает новую версию после каждого сохранения файла.
+pub fn start_rules_watcher(tx: Sender<Result<RulesPack, RulesError>>) {
+    thread::spawn(move || {
+        let mut last_modified = modified_time();
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let current = modified_time();
+            if current.is_none() || current == last_modified { continue; }
+            last_modified = current;
+
+            // Редактор мог еще не дописать файл - даем ему немного времени
+            thread::sleep(Duration::from_millis(200));
+            let result = match fs::read_to_string(RULES_FILE) {
+                Ok(json) => parse_rules(&json),
+                Err(e) => Err(RulesError { path: String::new(), line: 0, column: 0, message: e.to_string() }),
+            };
+            log(&format!("Rules: {} changed, reloading ({})", RULES_FILE, if result.is_ok() { "OK" } else { "ERROR" }));
+            if tx.send(result).is_err() { break; }
+        }
+    });
+}
+
+fn read_pack(path: &str) -> Option<RulesPack> {
+    fs::read_to_string(path).ok().and_then(|json| parse_rules(&json).ok())
+}
+
+fn write_pack(path: &str, pack: &RulesPack) {
+    if let Ok(json) = serde_json::to_string(pack) {
+        let _ = fs::write(path, json);
+    }
+}
+
+/// Запоминает принятый пакет. Если вышла новая редакция, прошлая уходит в rules.prev.json.
+/// Возвращает предыдущий пакет для сравнения, если он есть.
+pub fn install_pack(pack: &RulesPack) -> Option<RulesPack> {
+    install_pack_at(pack, INSTALLED_FILE, PREVIOUS_FILE)
+}
+
+fn install_pack_at(pack: &RulesPack, installed_path: &str, previous_path: &str) -> Option<RulesPack> {
+    match read_pack(installed_path) {
+        Some(installed) if installed.same_release(pack) => {}
+        Some(installed) => {
+            log(&format!("Rules: new pack {} (was {})", pack.title(), installed.title()));
+            write_pack(previous_path, &installed);
+            write_pack(installed_path, pack);
+        }
+        None => write_pack(installed_path, pack),
+    }
+    read_pack(previous_path)
+}
+
+// ================= СРАВНЕНИЕ РЕДАКЦИЙ =================
+
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+    /// Изменились сроки наказания, а не текст
+    pub is_punishment: bool,
+}
+
+pub enum RuleChange {
+    Added(Rule),
+    Removed(Rule),
+    Changed { rule: Rule, fields: Vec<FieldChange> },
+}
+
+fn compared_fields(rule: &Rule) -> [(&'static str, String, bool); 10] {
+    [
+        ("Название", rule.title.clone(), false),
+        ("Описание", rule.description.clone(), false),
+        ("Бан", rule.ban.clone(), true),
+        ("Варн", rule.warn.to_string(), true),
+        ("Деморган", rule.demorgan.clone(), true),
+        ("Пацифист", rule.pacifist.clone(), true),
+        ("Мут голоса", rule.mutev.clone(), true),
+        ("Мут чата", rule.mute_chat.clone(), true),
+        ("Мут репорта", rule.mute_report.clone(), true),
+        ("Бан маркета", rule.ban_market.clone(), true),
+    ]
+}
+
+/// Статьи сопоставляются по паре (категория, номер): номера вида "1.1 ПП"
+/// повторяются в разных разделах регламента.
+pub fn diff_rules(old: &[Rule], new: &[Rule]) -> Vec<RuleChange> {
+    let key = |r: &Rule| (r.category.clone(), r.article.clone());
+    let mut old_map: HashMap<(String, String), &Rule> = HashMap::new();
+    for r in old { old_map.entry(key(r)).or_insert(r); }
+    let mut new_map: HashMap<(String, String), &Rule> = HashMap::new();
+    for r in new { new_map.entry(key(r)).or_insert(r); }
+
+    let mut changes = Vec::new();
+    for r in new {
+        let k = key(r);
+        if !std::ptr::eq(new_map[&k], r) { continue; }
+        match old_map.get(&k) {
+            None => changes.push(RuleChange::Added(r.clone())),
+            Some(prev) => {
+                let fields: Vec<FieldChange> = compared_fields(prev)
+                    .into_iter()
+                    .zip(compared_fields(r))
+                    .filter(|(a, b)| a.1 != b.1)
+                    .map(|(a, b)| FieldChange { field: a.0, old: a.1, new: b.1, is_punishment: a.2 })
+                    .collect();
+                if !fields.is_empty() { changes.push(RuleChange::Changed { rule: r.clone(), fields }); }
+            }
+        }
+    }
+    for r in old {
+        let k = key(r);
+        if std::ptr::eq(old_map[&k], r) && !new_map.contains_key(&k) {
+            changes.push(RuleChange::Removed(r.clone()));
+        }
+    }
+    changes
+}
+
+// ================= ДЕРЕВО КАТЕГОРИЙ =================
+
+pub struct RuleCategory {
+    pub name: String,
+    /// Индексы в списке правил, отсортированные по номеру статьи
+    pub rules: Vec<usize>,
+}
+
+/// Ключ сортировки номера статьи: "3.2.1 ПП" → ([3, 2, 1], "пп").
+/// Числа сравниваются как числа, поэтому 3.2 < 3.2.1 < 3.10.
+pub fn article_key(article: &str) -> (Vec<u32>, String) {
+    let article = article.trim();
+    let (number, suffix) = article.split_once(' ').unwrap_or((article, ""));
+    let parts: Vec<u32> = number.split('.').map_while(|p| p.parse().ok()).collect();
+    (parts, suffix.trim().to_lowercase())
+}
+
+/// Категории в порядке регламента, статьи внутри - по номеру.
+pub fn category_tree(rules: &[Rule]) -> Vec<RuleCategory> {
+    let mut tree: Vec<RuleCategory> = Vec::new();
+    for (i, r) in rules.iter().enumerate() {
+        match tree.iter_mut().find(|c| c.name == r.category) {
+            Some(c) => c.rules.push(i),
+            None => tree.push(RuleCategory { name: r.category.clone(), rules: vec![i] }),
+        }
+    }
+    for c in &mut tree {
+        // Стабильная сортировка: одинаковые номера остаются в порядке файла
+        c.rules.sort_by_cached_key(|&i| article_key(&rules[i].article));
+    }
+    tree
+}
+
+// ================= ССЫЛКИ МЕЖДУ СТАТЬЯМИ =================
+// В описаниях встречается "согласно 5.15 ПП" или "пункта 1.18 данного свода".
+// Номер с сокращением ищется как есть, номер без сокращения - в своде текущей статьи.
+
+/// "3.2 ПП" → "ПП"
+pub fn article_suffix(article: &str) -> &str {
+    article.trim().split_once(' ').map(|(_, s)| s.trim()).unwrap_or("")
+}
+
+/// "2.2. ОПКО" и "2.2 ОПКО" - одна и та же статья.
+fn reference_key(number: &str, suffix: &str) -> String {
+    format!("{} {}", number.trim().trim_end_matches('.'), suffix.trim())
+}
+
+pub enum DescPart {
+    Text(String),
+    /// Текст ссылки как в описании и индекс статьи, на которую она указывает
+    Link(String, usize),
+}
+
+/// Ссылка в тексте: (начало, конец в байтах, номер, сокращение если указано).
+fn find_references(text: &str, suffixes: &[&str]) -> Vec<(usize, usize, String, Option<String>)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte_at = |i: usize| chars.get(i).map(|c| c.0).unwrap_or(text.len());
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let prev = if i > 0 { Some(chars[i - 1].1) } else { None };
+        if !chars[i].1.is_ascii_digit() || prev.map(|p| p.is_alphanumeric() || p == '.').unwrap_or(false) { i += 1; continue; }
+        // Номер вида 5.15 или 3.2.1 - хотя бы одна точка между цифрами
+        let start = i;
+        let mut dots = 0;
+        while i < chars.len() && (chars[i].1.is_ascii_digit() || (chars[i].1 == '.' && chars.get(i + 1).map(|c| c.1.is_ascii_digit()).unwrap_or(false))) {
+            if chars[i].1 == '.' { dots += 1; }
+            i += 1;
+        }
+        if dots == 0 { continue; }
+        let number = text[byte_at(start)..byte_at(i)].to_string();
+        let mut end = i;
+        if chars.get(end).map(|c| c.1 == '.').unwrap_or(false) { end += 1; }
+        let mut word_start = end;
+        while chars.get(word_start).map(|c| c.1 == ' ').unwrap_or(false) { word_start += 1; }
+        let mut word_end = word_start;
+        while chars.get(word_end).map(|c| c.1.is_alphabetic()).unwrap_or(false) { word_end += 1; }
+        let word = &text[byte_at(word_start)..byte_at(word_end)];
+        if word_end > word_start && suffixes.contains(&word) {
+            found.push((byte_at(start), byte_at(word_end), number, Some(word.to_string())));
+            i = word_end;
+        } else {
+            found.push((byte_at(start), byte_at(i), number, None));
+        }
+    }
+    found
+}
+
+/// Ссылки между статьями; строится один раз при загрузке правил.
+#[derive(Default)]
+pub struct RuleRefs {
+    by_article: HashMap<String, usize>,
+    suffixes: Vec<String>,
+    /// Для каждой статьи - статьи, которые на нее ссылаются
+    pub referenced_by: Vec<Vec<usize>>,
+}
+
+impl RuleRefs {
+    pub fn build(rules: &[Rule]) -> Self {
+        let mut refs = RuleRefs { referenced_by: vec![Vec::new(); rules.len()], ..Default::default() };
+        for (i, r) in rules.iter().enumerate() {
+            let suffix = article_suffix(&r.article);
+            let number = r.article.trim().split(' ').next().unwrap_or("");
+            refs.by_article.entry(reference_key(number, suffix)).or_insert(i);
+            if !suffix.is_empty() && !refs.suffixes.iter().any(|s| s == suffix) { refs.suffixes.push(suffix.to_string()); }
+        }
+        for i in 0..rules.len() {
+            for target in refs.targets(rules, i) {
+                // Повторы статьи в файле считаются одним источником
+                let known = refs.referenced_by[target].iter().any(|&j| rules[j].article == rules[i].article && rules[j].category == rules[i].category);
+                if !known { refs.referenced_by[target].push(i); }
+            }
+        }
+        refs
+    }
+
+    fn resolve(&self, rules: &[Rule], from: usize, number: &str, suffix: Option<&str>) -> Option<usize> {
+        let suffix = suffix.unwrap_or_else(|| article_suffix(&rules[from].article));
+        self.by_article.get(&reference_key(number, suffix)).copied().filter(|&t| t != from)
+    }
+
+    fn references(&self, text: &str) -> Vec<(usize, usize, String, Option<String>)> {
+        let suffixes: Vec<&str> = self.suffixes.iter().map(|s| s.as_str()).collect();
+        find_references(text, &suffixes)
+    }
+
+    fn targets(&self, rules: &[Rule], from: usize) -> Vec<usize> {
+        self.references(&rules[from].description).into_iter().filter_map(|(_, _, n, s)| self.resolve(rules, from, &n, s.as_deref())).collect()
+    }
+
+    /// Описание статьи, разбитое на текст и ссылки на существующие статьи.
+    pub fn split(&self, rules: &[Rule], from: usize, text: &str) -> Vec<DescPart> {
+        let mut parts = Vec::new();
+        let mut last = 0;
+        for (start, end, number, suffix) in self.references(text) {
+            let Some(target) = self.resolve(rules, from, &number, suffix.as_deref()) else { continue };
+            if start > last { parts.push(DescPart::Text(text[last..start].to_string())); }
+            parts.push(DescPart::Link(text[start..end].to_string(), target));
+            last = end;
+        }
+        if last < text.len() { parts.push(DescPart::Text(text[last..].to_string())); }
+        parts
+    }
+
+    /// Ссылки с явным сокращением ("5.15 ПП"), для которых нет статьи.
+    pub fn broken(&self, rules: &[Rule], from: usize) -> Vec<String> {
+        self.references(&rules[from].description)
+            .into_iter()
+            .filter(|(_, _, n, s)| s.as_deref().map(|s| !self.by_article.contains_key(&reference_key(n, s))).unwrap_or(false))
+            .map(|(_, _, n, s)| format!("{} {}", n, s.unwrap_or_default()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(version: &str, date: &str) -> RulesPack {
+        RulesPack { version: version.into(), date: date.into(), rules: Vec::new() }
+    }
+
+    fn rule(category: &str, article: &str, title: &str) -> Rule {
+        Rule::for_test(category, article, title)
+    }
+
+    /// "+" добавлена, "-" удалена, "~" изменена (с "!" у сроков наказания).
+    fn summary(changes: &[RuleChange]) -> Vec<String> {
+        changes.iter().map(|c| match c {
+            RuleChange::Added(r) => format!("+{}", r.article),
+            RuleChange::Removed(r) => format!("-{}", r.article),
+            RuleChange::Changed { rule, fields } => {
+                let fields: Vec<String> = fields.iter().map(|f| format!("{}{}", f.field, if f.is_punishment { "!" } else { "" })).collect();
+                format!("~{} {}", rule.article, fields.join(","))
+            }
+        }).collect()
+    }
+
+    #[test]
+    fn diff_finds_added_removed_and_changed() {
+        let old = [Rule { ban: "3d".into(), ..rule("ПП", "1.1 ПП", "Мат") }, rule("ПП", "1.2 ПП", "Флуд"), rule("ПП", "1.3 ПП", "Капс")];
+        let new = [Rule { ban: "7d".into(), ..rule("ПП", "1.1 ПП", "Мат") }, rule("ПП", "1.2 ПП", "Флуд в чате"), rule("ПП", "1.4 ПП", "Оффтоп")];
+        assert_eq!(summary(&diff_rules(&old, &new)), ["~1.1 ПП Бан!", "~1.2 ПП Название", "+1.4 ПП", "-1.3 ПП"]);
+        assert!(diff_rules(&old, &old).is_empty());
+        let RuleChange::Changed { fields, .. } = &diff_rules(&old, &new)[0] else { panic!("ожидалось изменение") };
+        assert_eq!((fields[0].old.as_str(), fields[0].new.as_str()), ("3d", "7d"));
+    }
+
+    #[test]
+    fn diff_uses_first_of_duplicate_articles() {
+        let old = [rule("ПП", "1.1 ПП", "Мат"), rule("ПП", "1.1 ПП", "Мат (повтор)")];
+        let new = [rule("ПП", "1.1 ПП", "Мат"), rule("ПП", "1.1 ПП", "Другой текст")];
+        assert!(diff_rules(&old, &new).is_empty());
+        assert!(diff_rules(&old, &old[..1]).is_empty());
+        // Одинаковый номер в разных разделах - разные статьи
+        let new = [rule("ПП", "1.1 ПП", "Мат"), rule("ПВ", "1.1 ПП", "Другая")];
+        assert_eq!(summary(&diff_rules(&old, &new)), ["+1.1 ПП"]);
+    }
+
+    #[test]
+    fn install_pack_keeps_previous_release() {
+        let dir = std::env::temp_dir();
+        let path = |name: &str| dir.join(format!("admin_helper_{}_{}.json", name, std::process::id())).to_string_lossy().into_owned();
+        let (installed, previous) = (path("installed"), path("previous"));
+        let _ = (fs::remove_file(&installed), fs::remove_file(&previous));
+
+        let v1 = RulesPack { rules: vec![rule("ПП", "1.1 ПП", "Мат")], ..pack("1.0", "2026-09-01") };
+        let v2 = RulesPack { rules: vec![rule("ПП", "1.1 ПП", "Мат"), rule("ПП", "1.2 ПП", "Флуд")], ..pack("1.1", "2026-10-01") };
+        assert!(install_pack_at(&v1, &installed, &previous).is_none());
+        assert!(install_pack_at(&v1, &installed, &previous).is_none());
+        let prev = install_pack_at(&v2, &installed, &previous).expect("прошлая редакция сохранена");
+        assert_eq!(prev.version, "1.0");
+        assert_eq!(summary(&diff_rules(&prev.rules, &v2.rules)), ["+1.2 ПП"]);
+        // Повторный запуск той же редакции показывает то же сравнение
+        assert_eq!(install_pack_at(&v2, &installed, &previous).map(|p| p.version), Some("1.0".to_string()));
+        let _ = (fs::remove_file(&installed), fs::remove_file(&previous));
+    }
+
+    #[test]
+    fn newer_pack_by_version_then_date() {
+        assert!(pack("1.10", "").is_newer_than(&pack("1.9", "")));
+        assert!(pack("2", "").is_newer_than(&pack("1.9.9", "")));
+        assert!(pack("1.0", "2026-10-18").is_newer_than(&pack("1.0", "2026-09-01")));
+        assert!(pack("1.0", "").is_newer_than(&pack("", "2030-01-01")));
+        assert!(!pack("1.0", "2026-10-18").is_newer_than(&pack("1.0", "2026-10-18")));
+        assert!(!pack("1.0", "").is_newer_than(&pack("1.1", "")));
+    }
+
+    #[test]
+    fn articles_sort_by_numbers() {
+        assert!(article_key("3.2 ПП") < article_key("3.2.1 ПП"));
+        assert!(article_key("3.2.1 ПП") < article_key("3.10 ПП"));
+        assert!(article_key("9.1 ПП") < article_key("10.1 ПП"));
+        assert_eq!(article_key(" 2. ПИМ "), (vec![2], "пим".to_string()));
+    }
+
+    #[test]
+    fn categories_keep_file_order() {
+        let rules = [rule("Б", "3.10 ПП", ""), rule("А", "1.1 ОП", ""), rule("Б", "3.2 ПП", ""), rule("Б", "3.2.1 ПП", ""), rule("А", "1.1 ОП", "повтор")];
+        let tree = category_tree(&rules);
+        assert_eq!(tree.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), ["Б", "А"]);
+        assert_eq!(tree[0].rules, [2, 3, 0]);
+        // Одинаковые номера - в порядке файла
+        assert_eq!(tree[1].rules, [1, 4]);
+    }
+
+    fn described(category: &str, article: &str, description: &str) -> Rule {
+        Rule { description: description.into(), ..rule(category, article, "") }
+    }
+
+    /// Ссылки в описании статьи `from`: (текст ссылки, номер статьи, на которую она ведет).
+    fn links(rules: &[Rule], from: usize) -> Vec<(String, String)> {
+        let refs = RuleRefs::build(rules);
+        refs.split(rules, from, &rules[from].description).into_iter().filter_map(|p| match p {
+            DescPart::Link(text, target) => Some((text, format!("{} [{}]", rules[target].article, rules[target].category))),
+            DescPart::Text(_) => None,
+        }).collect()
+    }
+
+    #[test]
+    fn references_resolve_in_own_code_or_by_suffix() {
+        let rules = [
+            described("Правила проекта", "2.2 ПП", ""),
+            described("Организации", "2.2 ОПКО", ""),
+            described("Правила проекта", "1.1 ПП", "см. п. 2.2 и 2.2 ОПКО"),
+            described("Организации", "1.1 ОПКО", "согласно пункта 2.2. данного свода, 2.2. ПП"),
+        ];
+        let link = |text: &str, target: &str| (text.to_string(), target.to_string());
+        assert_eq!(links(&rules, 2), [link("2.2", "2.2 ПП [Правила проекта]"), link("2.2 ОПКО", "2.2 ОПКО [Организации]")]);
+        assert_eq!(links(&rules, 3), [link("2.2", "2.2 ОПКО [Организации]"), link("2.2. ПП", "2.2 ПП [Правила проекта]")]);
+        let refs = RuleRefs::build(&rules);
+        assert_eq!(refs.referenced_by[0], [2, 3]);
+        assert_eq!(refs.referenced_by[1], [2, 3]);
+    }
+
+    #[test]
+    fn self_links_and_plain_numbers_are_ignored() {
+        let rules = [described("ПП", "2.2 ПП", "как в 2.2 ПП, штраф 1.5 раза, версия 10, п.3"), described("ПП", "3.1 ПП", "")];
+        assert!(links(&rules, 0).is_empty());
+        assert!(RuleRefs::build(&rules).referenced_by[0].is_empty());
+    }
+
+    #[test]
+    fn broken_reports_only_explicit_missing_articles() {
+        let rules = [described("ПП", "1.1 ПП", "согласно 5.15 ПП и 5.16, а также 1.1 ПП"), described("ПП", "1.2 ПП", "")];
+        let refs = RuleRefs::build(&rules);
+        assert_eq!(refs.broken(&rules, 0), ["5.15 ПП"]);
+        assert!(refs.broken(&rules, 1).is_empty());
+    }
+}
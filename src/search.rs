@@ -0,0 +1,307 @@
+use crate::Rule;
+use eframe::egui;
+use std::ops::Range;
+
+// ================= ПОИСК ПО ПРАВИЛАМ =================
+// Ищем по номеру, названию, категории и описанию статьи.
+// Запрос разбивается на слова, каждое слово должно найтись хоть где-то.
+// Прощаем опечатки (1-2 буквы) и набор не в той раскладке ("lbcrjhl" = "дискорд").
+
+const W_ARTICLE: u32 = 100;
+const W_TITLE: u32 = 40;
+const W_CATEGORY: u32 = 15;
+const W_DESCRIPTION: u32 = 10;
+
+const LAYOUT_EN: &str = "qwertyuiop[]asdfghjkl;'zxcvbnm,.`";
+const LAYOUT_RU: &str = "йцукенгшщзхъфывапролджэячсмитьбюё";
+
+struct IndexedRule {
+    article: String,
+    title: String,
+    category: String,
+    description: String,
+    /// Описание как в правилах (для отрывков); `description` - оно же после `normalize`
+    display_description: String,
+    /// Слова статьи (для поиска с опечатками) с весом поля, где слово встретилось
+    words: Vec<(String, Vec<char>, u32)>,
+}
+
+pub struct RuleIndex {
+    entries: Vec<IndexedRule>,
+}
+
+#[derive(Clone)]
+pub struct SearchHit {
+    pub index: usize,
+    pub score: u32,
+    /// Что подсветить в выдаче: слова запроса или найденные по ним слова с опечаткой
+    pub terms: Vec<String>,
+    /// Кусок описания, если совпадение только там
+    pub snippet: Option<String>,
+}
+
+/// Нижний регистр и "ё" → "е". Число символов почти всегда то же, но не всегда:
+/// "İ" в нижнем регистре - два символа.
+fn normalize(s: &str) -> String {
+    s.to_lowercase().replace('ё', "е")
+}
+
+fn split_words(s: &str) -> impl Iterator<Item = &str> {
+    s.split(|c: char| !c.is_alphanumeric()).filter(|w| w.chars().count() > 1)
+}
+
+/// Перевод набранного в другой раскладке: "ghbdtn" → "привет", "руддщ" → "hello".
+pub fn swap_layout(s: &str) -> String {
+    s.chars().map(swap_layout_char).collect()
+}
+
+/// Символ с той же клавиши в другой раскладке: 'g' ↔ 'п'. Остальное без изменений.
+pub fn swap_layout_char(c: char) -> char {
+    if let Some(i) = LAYOUT_EN.chars().position(|e| e == c) {
+        LAYOUT_RU.chars().nth(i).unwrap_or(c)
+    } else if let Some(i) = LAYOUT_RU.chars().position(|r| r == c) {
+        LAYOUT_EN.chars().nth(i).unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+/// Расстояние Левенштейна с отсечкой: если точно больше `limit`, возвращает limit + 1.
+fn edit_distance(a: &[char], b: &[char], limit: usize) -> usize {
+    if a.len().abs_diff(b.len()) > limit { return limit + 1; }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        let mut row_min = cur[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+            row_min = row_min.min(cur[j + 1]);
+        }
+        if row_min > limit { return limit + 1; }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+fn typo_limit(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+impl RuleIndex {
+    pub fn build(rules: &[Rule]) -> Self {
+        let entries = rules
+            .iter()
+            .map(|r| {
+                let title = normalize(&r.title);
+                let category = normalize(&r.category);
+                let display_description = r.description.replace("`n", "  ");
+                let description = normalize(&display_description);
+                let mut words: Vec<(String, Vec<char>, u32)> = Vec::new();
+                for (text, weight) in [(&title, W_TITLE), (&category, W_CATEGORY), (&description, W_DESCRIPTION)] {
+                    for w in split_words(text) {
+                        if !words.iter().any(|(x, _, _)| x == w) { words.push((w.to_string(), w.chars().collect(), weight)); }
+                    }
+                }
+                IndexedRule { article: normalize(&r.article), title, category, description, display_description, words }
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Оценка одного слова запроса для статьи + что подсветить.
+    fn score_token(entry: &IndexedRule, token: &str) -> (u32, Option<String>) {
+        let mut best = 0;
+        let mut term = None;
+
+        if entry.article.starts_with(token) {
+            // "3.2" выше, чем "3.2.1" и "13.2"
+            let exact = entry.article.split(' ').next() == Some(token);
+            best = if exact { W_ARTICLE * 3 } else { W_ARTICLE * 2 };
+        } else if entry.article.contains(token) {
+            best = W_ARTICLE;
+        }
+        for (text, weight) in [(&entry.title, W_TITLE), (&entry.category, W_CATEGORY), (&entry.description, W_DESCRIPTION)] {
+            if weight > best && text.contains(token) { best = weight; }
+        }
+        if best > 0 { return (best, Some(token.to_string())); }
+
+        let chars: Vec<char> = token.chars().collect();
+        let limit = typo_limit(chars.len());
+        if limit == 0 { return (0, None); }
+        for (word, word_chars, weight) in &entry.words {
+            if weight / 2 <= best { continue; }
+            // Опечатка в начале длинного слова: сравниваем и с его началом той же длины
+            let prefix = &word_chars[..word_chars.len().min(chars.len())];
+            if edit_distance(&chars, word_chars, limit) <= limit || edit_distance(&chars, prefix, limit) <= limit {
+                best = weight / 2;
+                term = Some(word.clone());
+            }
+        }
+        (best, term)
+    }
+
+    fn score_query(entry: &IndexedRule, tokens: &[String]) -> Option<(u32, Vec<String>)> {
+        let mut total = 0;
+        let mut terms = Vec::new();
+        for token in tokens {
+            let (score, term) = Self::score_token(entry, token);
+            if score == 0 { return None; }
+            total += score;
+            terms.extend(term);
+        }
+        Some((total, terms))
+    }
+
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let query = normalize(query.trim());
+        if query.is_empty() { return Vec::new(); }
+        let variants: Vec<Vec<String>> = [query.clone(), swap_layout(&query)]
+            .iter()
+            .map(|q| q.split_whitespace().map(|t| t.to_string()).collect())
+            .collect();
+
+        let mut hits: Vec<SearchHit> = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let best = variants.iter().filter_map(|tokens| Self::score_query(entry, tokens)).max_by_key(|(score, _)| *score);
+            let Some((score, terms)) = best else { continue };
+            let in_header = terms.iter().any(|t| entry.article.contains(t.as_str()) || entry.title.contains(t.as_str()));
+            let snippet = if in_header { None } else { terms.first().and_then(|t| snippet(&entry.display_description, &entry.description, t)) };
+            hits.push(SearchHit { index, score, terms, snippet });
+        }
+        // Сортировка стабильная: при равной оценке сохраняется порядок регламента
+        hits.sort_by_key(|h| std::cmp::Reverse(h.score));
+        hits
+    }
+}
+
+fn snippet(display: &str, normalized: &str, term: &str) -> Option<String> {
+    let byte_pos = normalized.find(term)?;
+    let chars: Vec<char> = display.chars().collect();
+    // Позиция считается по нормализованному тексту и может уехать за конец исходного
+    let pos = normalized[..byte_pos].chars().count().min(chars.len());
+    let start = pos.saturating_sub(30);
+    let end = (pos + term.chars().count() + 30).min(chars.len());
+    let body: String = chars[start..end].iter().collect();
+    Some(format!("{}{}{}", if start > 0 { "…" } else { "" }, body.trim(), if end < chars.len() { "…" } else { "" }))
+}
+
+/// Байтовые диапазоны вхождений `term` в `text` без учета регистра.
+fn find_ranges(text: &str, term: &str) -> Vec<Range<usize>> {
+    let needle: Vec<char> = term.chars().collect();
+    if needle.is_empty() { return Vec::new(); }
+    let hay: Vec<(usize, char)> = text.char_indices().map(|(i, c)| (i, normalize(&c.to_string()).chars().next().unwrap_or(c))).collect();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= hay.len() {
+        if hay[i..i + needle.len()].iter().map(|(_, c)| *c).eq(needle.iter().copied()) {
+            let end = hay.get(i + needle.len()).map(|(b, _)| *b).unwrap_or(text.len());
+            ranges.push(hay[i].0..end);
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
+/// Текст для кнопки/подписи с подсвеченными совпадениями.
+pub fn highlight(text: &str, terms: &[String], color: egui::Color32, accent: egui::Color32) -> egui::text::LayoutJob {
+    let mut ranges: Vec<Range<usize>> = terms.iter().flat_map(|t| find_ranges(text, t)).collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let plain = egui::TextFormat { color, ..Default::default() };
+    let marked = egui::TextFormat { color: accent, underline: egui::Stroke::new(1.0, accent), ..Default::default() };
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut pos = 0;
+    for r in ranges {
+        if r.start < pos { continue; }
+        job.append(&text[pos..r.start], 0.0, plain.clone());
+        job.append(&text[r.clone()], 0.0, marked.clone());
+        pos = r.end;
+    }
+    job.append(&text[pos..], 0.0, plain);
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titled(article: &str, title: &str) -> Rule {
+        Rule::for_test("Правила проекта", article, title)
+    }
+
+    fn found(index: &RuleIndex, query: &str) -> Vec<usize> {
+        index.search(query).into_iter().map(|h| h.index).collect()
+    }
+
+    fn ranges(text: &str, term: &str) -> Vec<(usize, usize)> {
+        find_ranges(text, term).into_iter().map(|r| (r.start, r.end)).collect()
+    }
+
+    #[test]
+    fn typos_within_budget() {
+        let index = RuleIndex::build(&[titled("1.1 ПП", "Оскорбление игроков"), titled("1.2 ПП", "Дискорд")]);
+        assert_eq!(found(&index, "оскарбление"), [0]);
+        assert_eq!(found(&index, "аскарбление"), [0]);
+        assert!(found(&index, "аскарблении").is_empty());
+        // В коротких словах опечаток не прощаем
+        assert_eq!(found(&index, "дискорт"), [1]);
+        assert!(found(&index, "иго").is_empty());
+    }
+
+    #[test]
+    fn wrong_layout_query() {
+        let index = RuleIndex::build(&[titled("1.1 ПП", "Оскорбление"), titled("1.2 ПП", "Дискорд")]);
+        assert_eq!(found(&index, "lbcrjhl"), [1]);
+        assert_eq!(found(&index, "LBCRJHL"), [1]);
+        assert_eq!(swap_layout("ghbdtn"), "привет");
+        assert_eq!(swap_layout("руддщ"), "hello");
+    }
+
+    #[test]
+    fn exact_before_prefix_before_fuzzy() {
+        let index = RuleIndex::build(&[titled("13.2 ПП", "Флуд"), titled("3.2.1 ПП", "Капс"), titled("3.2 ПП", "Мат")]);
+        assert_eq!(found(&index, "3.2"), [2, 1, 0]);
+        let index = RuleIndex::build(&[titled("1.1 ПП", "Оскарбление"), titled("1.2 ПП", "Оскорбление")]);
+        let hits = index.search("оскорбление");
+        assert_eq!(hits.iter().map(|h| h.index).collect::<Vec<_>>(), [1, 0]);
+        assert_eq!(hits[1].terms, ["оскарбление"]);
+        // При равной оценке - порядок регламента
+        let index = RuleIndex::build(&[titled("1.1 ПП", "Мат в чате"), titled("1.2 ПП", "Мат в голосе")]);
+        assert_eq!(found(&index, "мат"), [0, 1]);
+    }
+
+    #[test]
+    fn yo_folds_to_ye() {
+        let index = RuleIndex::build(&[titled("1.1 ПП", "Ёлка"), titled("1.2 ПП", "Ещё")]);
+        assert_eq!(found(&index, "елка"), [0]);
+        assert_eq!(found(&index, "ЕЩЁ"), [1]);
+        assert_eq!(ranges("Ёлка", "елка"), [(0, 8)]);
+    }
+
+    #[test]
+    fn highlight_ranges_are_byte_ranges() {
+        assert_eq!(ranges("Дискорд и ДИСКОРД", "дискорд"), [(0, 14), (18, 32)]);
+        assert_eq!(ranges("ааа", "аа"), [(0, 4)]);
+        assert!(find_ranges("мат", "").is_empty());
+        let job = highlight("3.2 Мат", &["мат".to_string()], egui::Color32::WHITE, egui::Color32::RED);
+        assert_eq!(job.sections.iter().map(|s| &job.text[s.byte_range.clone()]).collect::<Vec<_>>(), ["3.2 ", "Мат", ""]);
+    }
+
+    #[test]
+    fn snippet_survives_longer_lowercase() {
+        let mut rule = titled("1.1 ПП", "Правило");
+        rule.description = format!("{} нарушение", "İ".repeat(40));
+        let hits = RuleIndex::build(&[rule]).search("нарушение");
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].snippet.is_some());
+    }
+}